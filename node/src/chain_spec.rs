@@ -1,6 +1,6 @@
 use node_hodl_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY, KryptoKurrencyConfig,
+	SystemConfig, WASM_BINARY, KryptoKurrencyConfig, KryptConfig,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -155,8 +155,13 @@ fn testnet_genesis(
 			key: root_key,
 		},
 		transaction_payment: Default::default(),
+		krypt: KryptConfig {
+			balances: vec![],
+			// Use the runtime's MaxTokenSupply as the initial supply cap
+			supply_cap: None,
+		},
 		krypto_kurrency : KryptoKurrencyConfig {
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 100)).collect(),
+			balances: endowed_accounts.iter().cloned().map(|k| (k, 100, 0)).collect(),
 			max_token_supply : Some(200_000)
 		}
 	}
@@ -0,0 +1,44 @@
+//! Benchmarks for the krypt pallet
+//! Run them once with and once without the `trusted-account-ids` feature to compare the cost
+//! of `BalanceToAccount` lookups under `Blake2_128Concat` and `Twox64Concat`
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::EnsureOrigin;
+use frame_system::RawOrigin;
+use sp_runtime::traits::{StaticLookup, Zero};
+
+const SEED: u32 = 0;
+
+/// Mint half of `MaxTokenSupply` to `who` and hand back the amount
+fn fund<T: Config>(who: &T::AccountId) -> T::Balance {
+	let amount = T::MaxTokenSupply::get() / 2u32.into();
+	Pallet::<T>::mint(T::MintOrigin::successful_origin(), amount, who.clone())
+		.expect("minting half the supply cap to a fresh account works");
+	amount
+}
+
+benchmarks! {
+	// A lone `BalanceToAccount` read, the part of every transfer the hasher choice changes
+	balance_read {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let mut balance = T::Balance::zero();
+	}: {
+		balance = BalanceToAccount::<T>::get(&caller);
+	}
+	verify {
+		assert!(!balance.is_zero());
+	}
+
+	transfer_from {
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = fund::<T>(&caller) / 2u32.into();
+		let recipient: T::AccountId = account("recipient", 0, SEED);
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+		let recipient_lookup = T::Lookup::unlookup(recipient.clone());
+	}: _(RawOrigin::Signed(caller.clone()), caller_lookup, recipient_lookup, amount)
+	verify {
+		assert!(!Pallet::<T>::get_balance_of(&recipient).is_zero());
+	}
+}
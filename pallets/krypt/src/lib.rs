@@ -14,19 +14,46 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 pub use pallet::*;
 
+/// Callback for every change to a krypt balance, e.g. for analytics or mirroring pallets
+pub trait OnBalanceChanged<AccountId, Balance> {
+	/// Balance of `who` went from `old` to `new`
+	fn on_changed(who: &AccountId, old: Balance, new: Balance);
+}
+
+impl<AccountId, Balance> OnBalanceChanged<AccountId, Balance> for () {
+	fn on_changed(_: &AccountId, _: Balance, _: Balance) {}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+// The dispatch code `pallet::call` generates still calls the deprecated `total_issuance`
+#[allow(deprecated)]
 #[frame_support::pallet]
 pub mod pallet {
+	use crate::OnBalanceChanged;
 	use codec::{Codec, MaxEncodedLen};
-	use core::convert::Infallible;
 	#[allow(unused)]
 	use frame_support::traits::{Currency, Imbalance, TryDrop};
 	use frame_support::{
-		dispatch::{DispatchErrorWithPostInfo, DispatchResult, DispatchResultWithPostInfo},
-		traits::tokens::Balance,
+		dispatch::{
+			DispatchErrorWithPostInfo, DispatchResult, DispatchResultWithPostInfo,
+			WithPostDispatchInfo,
+		},
+		pallet_prelude::*,
+		traits::{tokens::Balance, GetStorageVersion, StorageVersion},
+		Blake2_128Concat, RuntimeDebug, Twox64Concat,
+	};
+	use frame_system::{
+		ensure_signed,
+		offchain::{SendTransactionTypes, SubmitTransaction},
+		pallet_prelude::*,
 	};
-	use frame_support::{pallet_prelude::*, Blake2_128Concat, Twox64Concat};
-	use frame_system::{ensure_root, ensure_signed, pallet_prelude::*};
-	use sp_std::{fmt::Debug, iter::Sum};
+	use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, iter::Sum, vec::Vec};
 	// use frame_support::{
 	// 	sp_runtime::traits::{Hash, Zero},
 	// 	dispatch::{DispatchResultWithPostInfo, DispatchResult},
@@ -36,16 +63,25 @@ pub mod pallet {
 	use scale_info::TypeInfo;
 	// use sp_io::hashing::blake2_128;
 	use sp_runtime::{
-		traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedSub, Saturating, StaticLookup, Zero},
-		ArithmeticError,
+		helpers_128bit::multiply_by_rational,
+		traits::{
+			AtLeast32BitUnsigned, Bounded, CheckedAdd, CheckedSub, IdentifyAccount,
+			MaybeSerializeDeserialize, One, Saturating, StaticLookup, Verify, Zero,
+		},
+		ArithmeticError, Perbill, Permill, SaturatedConversion,
 	};
 
+	/// Version of the storage layout, bumped alongside every migration
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::generate_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 		type Event: IsType<<Self as frame_system::Config>::Event> + From<Event<Self>>;
 		type Balance: Member
 			+ Parameter
@@ -58,147 +94,2755 @@ pub mod pallet {
 			+ Sum
 			+ Zero
 			+ Copy
-			+ MaxEncodedLen;
+			+ MaxEncodedLen
+			+ MaybeSerializeDeserialize;
 		#[pallet::constant]
 		type MaxTokenSupply: Get<Self::Balance>;
+		/// Maximum number of legs allowed in a single `transfer_batch`
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+		/// Minimum number of blocks funds must be held before they can be burned
+		/// Setting this to zero disables the check
+		#[pallet::constant]
+		type MinHoldBeforeBurn: Get<Self::BlockNumber>;
+		/// When true, a mint exceeding the supply cap mints only the remaining headroom
+		/// instead of failing
+		#[pallet::constant]
+		type PartialMintAllowed: Get<bool>;
+		/// When true, `total_issuance` doesn't emit `TotalIssued` again if the value hasn't
+		/// changed since it was last emitted
+		#[pallet::constant]
+		type SuppressRedundantSnapshots: Get<bool>;
+		/// Maximum amount that can be minted within a single block
+		/// Setting this to zero disables the limit
+		#[pallet::constant]
+		type MintRatePerBlock: Get<Self::Balance>;
+		/// When true, record who created an account through a transfer and when, in
+		/// `AccountOrigin`
+		#[pallet::constant]
+		type RecordAccountOrigin: Get<bool>;
+		/// Maximum length in bytes of a `transfer_with_memo` memo
+		#[pallet::constant]
+		type MaxMemoLength: Get<u32>;
+		/// Origin allowed to perform administrative actions such as changing the supply cap
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+		/// Balance keep-alive transfers must leave the sender with
+		/// Accounts holding less are reaped, burning their balance, during idle time
+		#[pallet::constant]
+		type ExistentialDeposit: Get<Self::Balance>;
+		/// Fraction of every transfer that is burned rather than delivered, offers included
+		/// Setting this to zero disables the fee
+		#[pallet::constant]
+		type TransferFeeRate: Get<Permill>;
+		/// Origin allowed to mint new tokens, e.g. root, a collective or a dedicated minter
+		type MintOrigin: EnsureOrigin<Self::Origin>;
+		/// Account receiving whatever goes over an account's self-imposed maximum balance
+		/// When `None`, credits that would go over a self-imposed cap fail instead
+		type SelfCapOverflowSink: Get<Option<Self::AccountId>>;
+		/// Number of `TotalIssued` snapshots kept in `SupplyHistory`
+		#[pallet::constant]
+		type HistoryDepth: Get<u32>;
+		/// Number of decimal places a whole token is split into
+		#[pallet::constant]
+		type Decimals: Get<u8>;
+		/// Maximum length in bytes of the token name and symbol
+		#[pallet::constant]
+		type MaxMetadataLength: Get<u32>;
+		/// Maximum number of accounts returned by a single `accounts_page` call
+		#[pallet::constant]
+		type MaxAccountsPage: Get<u32>;
+		/// Number of blocks between two `SupplyStats` updates by the offchain worker
+		/// Setting this to zero disables the worker
+		#[pallet::constant]
+		type StatsInterval: Get<Self::BlockNumber>;
+		/// Number of blocks a transfer offer stays open before it is returned to the sender
+		#[pallet::constant]
+		type OfferExpiry: Get<Self::BlockNumber>;
+		/// Maximum number of transfer offers that may expire in the same block
+		#[pallet::constant]
+		type MaxOffersPerBlock: Get<u32>;
+		/// Most a single account may hold, `None` for no limit
+		/// Accounts in `BalanceCapExempt` aren't bound by it
+		type MaxBalancePerAccount: Get<Option<Self::Balance>>;
+		/// Fraction of every balance, escrowed offer and of `TotalIssued` that decays away
+		/// each block. Setting this to zero disables decay. Builds with `derived-issuance`
+		/// never decay, since nothing may leave circulation there
+		#[pallet::constant]
+		type DecayRatePerBlock: Get<Permill>;
+		/// Called with the old and new balance whenever an account's balance is written
+		/// Decay isn't reported as it happens, only as part of the next write to the account
+		type OnBalanceChanged: OnBalanceChanged<Self::AccountId, Self::Balance>;
+		/// Smallest amount a single transfer may move, to keep dust transfers from spamming
+		/// the chain. Unlike `ExistentialDeposit` this limits transfer size rather than
+		/// balances. Setting this to zero disables it
+		#[pallet::constant]
+		type MinTransferAmount: Get<Self::Balance>;
+		/// Maximum length in bytes of the reason recorded when freezing an account
+		#[pallet::constant]
+		type MaxFreezeReasonLength: Get<u32>;
+		/// How mints and credits that would overflow the Balance type are handled
+		/// `false`, the safe choice, fails them with `MintTypeOverflow` or `BalanceOverflow`.
+		/// `true` saturates instead: mints are cut down to what still fits, and whatever
+		/// doesn't fit into a recipient's balance is destroyed and taken out of `TotalIssued`
+		#[pallet::constant]
+		type SaturatingArithmetic: Get<bool>;
+		/// Smallest amount a single mint may request, so dust mints can't flood the event
+		/// log with `MintedNewSupply`. Setting this to zero disables it
+		#[pallet::constant]
+		type MinMintAmount: Get<Self::Balance>;
+		/// How a fraction left by `TransferFeeRate` or `DecayRatePerBlock` is rounded, on the
+		/// fee itself and on the shrunk `DecayIndex`. Balances read through the index always
+		/// round down, so holdings never add up to more than `TotalIssued`
+		/// `RoundingPolicy::Down` keeps the behaviour from before this was configurable
+		#[pallet::constant]
+		type Rounding: Get<RoundingPolicy>;
+		/// Signature `permit` checks, usually the runtime's own extrinsic signature type
+		type Signature: Verify<Signer = Self::Signer> + Parameter;
+		/// Signer of `Signature`, resolving to the account that signed
+		type Signer: IdentifyAccount<AccountId = Self::AccountId>;
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
-		/// When minting overflows constant `MaxTokenSupply`
+		/// When minting overflows the supply cap, initially `MaxTokenSupply`
 		MintCausingTotalSupplyOverflow,
 		/// When minting overflows the bounds of the concrete type managing balances
 		MintTypeOverflow,
 		/// Insufficient Funds for operation
 		InsufficientFunds,
+		/// Account is frozen and can't move funds out
+		AccountFrozen,
+		/// Account isn't frozen
+		NotFrozen,
+		/// Freeze reason is longer than `MaxFreezeReasonLength`
+		FreezeReasonTooLong,
+		/// Destination is on the admin-managed blocklist
+		DestinationBlocked,
+		/// Mint requests less than `MinMintAmount`
+		BelowMinMint,
+		/// The admin origin paused all transfers
+		TransfersPaused,
+		/// Batch contains more legs than `MaxBatchSize`
+		BatchTooLarge,
+		/// Burner received funds less than `MinHoldBeforeBurn` blocks ago
+		HeldTooBriefly,
+		/// Spender's allowance doesn't cover the amount
+		InsufficientAllowance,
+		/// Recurring allowances need a period of at least one block
+		ZeroPeriod,
+		/// Mint would take this block's minted total over `MintRatePerBlock`
+		MintRateExceeded,
+		/// Memo is longer than `MaxMemoLength`
+		MemoTooLong,
+		/// Burning is disabled when the pallet is built with `derived-issuance`
+		BurnDisabled,
+		/// Supply cap can't be set below what has already been issued
+		CapBelowIssued,
+		/// Credit would take the recipient over its self-imposed maximum balance
+		SelfCapExceeded,
+		/// No open transfer offer with this id
+		OfferNotFound,
+		/// Only the recipient of an offer can accept it
+		NotOfferRecipient,
+		/// Only the sender of an offer can withdraw it
+		NotOfferSender,
+		/// Too many offers already expire in the same block as this one would
+		TooManyOffers,
+		/// Transfer would leave the sender with less than `ExistentialDeposit`
+		KeepAlive,
+		/// Token name or symbol is longer than `MaxMetadataLength`
+		MetadataTooLong,
+		/// Spender's allowance expired
+		AllowanceExpired,
+		/// Transfers and burns of zero are rejected rather than emitting no-op events
+		ZeroAmount,
+		/// Accounts can't approve themselves as spenders
+		ApproveSelf,
+		/// Credit would take a non-exempt account over `MaxBalancePerAccount`
+		BalanceCapExceeded,
+		/// Redenomination ratios need a nonzero numerator and denominator
+		InvalidRatio,
+		/// Credit would overflow the recipient's balance
+		BalanceOverflow,
+		/// Transfer moves less than `MinTransferAmount`
+		BelowMinTransfer,
+		/// More allowances exist than the `max_entries` witness given to `rescale_allowances`
+		TooManyAllowances,
+		/// Account isn't in `Minters`
+		NotMinter,
+		/// Permit is past its deadline
+		PermitExpired,
+		/// Permit signature isn't the owner's over the permit and its current nonce
+		InvalidPermitSignature,
+		/// A witness given to `redenominate` is below the number of entries it stands for
+		WitnessTooLow,
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		MintedNewSupply(<T as Config>::Balance),
+		/// New supply was minted to an account, along with the resulting `TotalIssued`
+		MintedNewSupply {
+			to: T::AccountId,
+			amount: T::Balance,
+			total_issued: T::Balance,
+		},
 		TransferSuccess(T::AccountId, T::AccountId, T::Balance),
 		// Writing <T as Config>::Balance in order to avoid confusion
 		// with the Runtime's instance of Balance (from Balances pallet)
 		// is not necessary because of the T: Conig trait bound on this Event
 		TotalIssued(T::Balance),
+		/// The admin origin froze an account, with the reason it gave
+		AccountFrozen(T::AccountId, BoundedVec<u8, T::MaxFreezeReasonLength>),
+		/// The admin origin lifted the freeze on an account
+		AccountThawed(T::AccountId),
+		/// Idle-time housekeeping burned the balance of an account below `ExistentialDeposit`
+		DustReaped {
+			who: T::AccountId,
+			amount: T::Balance,
+		},
+		/// The admin origin removed an account's storage, burning its whole balance
+		AccountWiped {
+			who: T::AccountId,
+			burned: T::Balance,
+		},
+		/// The admin origin stopped transfers to an account
+		DestinationBlocked(T::AccountId),
+		/// The admin origin allowed transfers to a blocked account again
+		DestinationUnblocked(T::AccountId),
+		/// The admin origin allowed an account to mint
+		MinterAdded(T::AccountId),
+		/// The admin origin took an account's right to mint away
+		MinterRemoved(T::AccountId),
+		/// The admin origin moved funds between accounts (from, to, amount)
+		ForceTransfer(T::AccountId, T::AccountId, T::Balance),
+		/// The admin origin paused or resumed all transfers
+		PauseSet(bool),
+		/// Account burned part of its balance
+		Burned(T::AccountId, T::Balance),
+		/// Mint was capped by the supply cap, or by the Balance type with `SaturatingArithmetic`
+		/// (requested, actually minted)
+		PartialMint(T::Balance, T::Balance),
+		/// Owner allowed spender to move amount (owner, spender, amount)
+		Approval(T::AccountId, T::AccountId, T::Balance),
+		/// Owner allowed spender to move amount every period blocks (owner, spender, amount,
+		/// period)
+		RecurringApproval(T::AccountId, T::AccountId, T::Balance, T::BlockNumber),
+		/// Owner allowed spender to move amount until block expiry (owner, spender, amount,
+		/// expiry)
+		ExpiringApproval(T::AccountId, T::AccountId, T::Balance, T::BlockNumber),
+		/// Some account voluntarily burned funds (who, amount)
+		CommunityBurn(T::AccountId, T::Balance),
+		/// Supply cap was changed by the admin origin
+		SupplyCapSet(T::Balance),
+		/// Spender used part of its allowance, `remaining` is what is left of it
+		SpentAllowance {
+			owner: T::AccountId,
+			spender: T::AccountId,
+			spent: T::Balance,
+			remaining: T::Balance,
+		},
+		/// Every balance was rescaled by `numerator / denominator`
+		Redenominated {
+			numerator: u32,
+			denominator: u32,
+		},
+		/// `count` allowances were rescaled by `numerator / denominator`
+		AllowancesRescaled {
+			numerator: u32,
+			denominator: u32,
+			count: u32,
+		},
+		/// A mint brought `TotalIssued` exactly to the supply cap, nothing more can be minted
+		SupplyCapReached(T::Balance),
+		/// Account set or cleared its own maximum balance (who, cap)
+		SelfMaxBalanceSet(T::AccountId, Option<T::Balance>),
+		/// Credit over an account's self-imposed cap was sent to the sink (who, sink, amount)
+		SelfCapOverflowRouted(T::AccountId, T::AccountId, T::Balance),
+		/// Admin origin exempted an account from `MaxBalancePerAccount`, or revoked it
+		/// (who, exempt)
+		BalanceCapExemptionSet(T::AccountId, bool),
+		/// Transfer tagged with a memo, e.g. an exchange deposit reference
+		TransferWithMemo {
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: T::Balance,
+			memo: BoundedVec<u8, T::MaxMemoLength>,
+		},
+		/// Funds were escrowed pending the recipient's acceptance (id, from, to, amount)
+		TransferOffered(u32, T::AccountId, T::AccountId, T::Balance),
+		/// Recipient accepted an offer and received its funds
+		TransferAccepted(u32),
+		/// Sender took back an offer before it was accepted
+		OfferWithdrawn(u32),
+		/// Offer wasn't accepted in time and its funds went back to the sender
+		OfferExpired(u32),
+		/// Token name and symbol were changed by the admin origin (name, symbol)
+		MetadataSet(BoundedVec<u8, T::MaxMetadataLength>, BoundedVec<u8, T::MaxMetadataLength>),
+		/// Part of a transfer was burned as a fee
+		TransferFeeBurned {
+			from: T::AccountId,
+			fee: T::Balance,
+		},
+		/// Offchain worker published fresh holder statistics
+		SupplyStatsUpdated(SupplyStatsSnapshot<T::Balance, T::BlockNumber>),
+		/// Balances of both ends right after a `transfer_from`, so clients can confirm it
+		/// without querying storage. `TransferSuccess` remains the canonical record
+		TransferReceipt {
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: T::Balance,
+			from_balance: T::Balance,
+			to_balance: T::Balance,
+		},
 	}
 
 	/// Total supply that has been so far minted and in circulation
 	/// Note: This is different from MaxTokenSupply which defines the upper limit for
 	/// the number of tokens
+	/// Kept in the same decay-index units as `BalanceToAccount`, so it is always exactly the
+	/// sum of all balances and escrowed offers. Read it through `total_issued`
 	#[pallet::storage]
-	#[pallet::getter(fn total_issued)]
 	pub(super) type TotalIssued<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
 
+	#[pallet::type_value]
+	pub(super) fn DefaultSupplyCap<T: Config>() -> T::Balance {
+		T::MaxTokenSupply::get()
+	}
+
+	/// Upper limit on `TotalIssued` currently in force
+	/// Starts out as the `MaxTokenSupply` constant and can be moved by the admin origin
+	#[pallet::storage]
+	#[pallet::getter(fn supply_cap)]
+	pub(super) type SupplyCap<T: Config> =
+		StorageValue<_, T::Balance, ValueQuery, DefaultSupplyCap<T>>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Accounts endowed at genesis, counted as minted
+		pub balances: Vec<(T::AccountId, T::Balance)>,
+		/// Initial `SupplyCap`, left at the `MaxTokenSupply` constant when `None`
+		pub supply_cap: Option<T::Balance>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { balances: Default::default(), supply_cap: None }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			let cap = self.supply_cap.unwrap_or_else(T::MaxTokenSupply::get);
+			let endowed = self
+				.balances
+				.iter()
+				.try_fold(T::Balance::zero(), |total, (_, amount)| total.checked_add(amount))
+				.expect("genesis balances overflow Balance type");
+			assert!(endowed <= cap, "Total sum in endowed accounts cannot exceed the supply cap");
+			let endowed_accounts = self
+				.balances
+				.iter()
+				.map(|(who, _)| who)
+				.collect::<std::collections::BTreeSet<_>>();
+			assert!(
+				endowed_accounts.len() == self.balances.len(),
+				"Duplicate entries for accounts in genesis"
+			);
+			if let Some(cap) = self.supply_cap {
+				SupplyCap::<T>::put(cap);
+			}
+			for (who, amount) in &self.balances {
+				// Nothing has decayed yet, so amounts and decay-index units are the same
+				Pallet::<T>::deposit_into(who, *amount);
+				Pallet::<T>::include_mint_amount(*amount, *amount);
+			}
+		}
+	}
+
+	/// Human readable token name, e.g. "Hodl"
+	#[pallet::storage]
+	#[pallet::getter(fn name)]
+	pub(super) type TokenName<T: Config> =
+		StorageValue<_, BoundedVec<u8, T::MaxMetadataLength>, ValueQuery>;
+
+	/// Ticker of the token, e.g. "HODL"
+	#[pallet::storage]
+	#[pallet::getter(fn symbol)]
+	pub(super) type TokenSymbol<T: Config> =
+		StorageValue<_, BoundedVec<u8, T::MaxMetadataLength>, ValueQuery>;
+
+	/// Sum of every amount ever minted, it never decreases, apart from `Issued` supply that is
+	/// dropped without ever being deposited
+	/// With the `derived-issuance` feature `TotalIssued` is recomputed from this counter on
+	/// every mint instead of being incremented on its own
+	#[pallet::storage]
+	#[pallet::getter(fn total_minted)]
+	pub(super) type TotalMinted<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Sum of every amount ever transferred between accounts, counted as sent, i.e. before
+	/// the transfer fee is taken out. Transfers to oneself move nothing and aren't counted
+	/// Unlike `TotalIssued` this only ever grows and measures activity rather than supply
+	#[pallet::storage]
+	#[pallet::getter(fn lifetime_volume)]
+	pub(super) type LifetimeVolume<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Total amount voluntarily burned through `donate_to_burn`
+	#[pallet::storage]
+	#[pallet::getter(fn community_burned)]
+	pub(super) type CommunityBurned<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Value carried by the last `TotalIssued` event
+	#[pallet::storage]
+	#[pallet::getter(fn last_reported_issuance)]
+	pub(super) type LastReportedIssuance<T: Config> = StorageValue<_, T::Balance>;
+
+	/// Amount minted so far in the current block, reset in `on_initialize`
+	#[pallet::storage]
+	#[pallet::getter(fn minted_this_block)]
+	pub(super) type MintedThisBlock<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Recent (block, TotalIssued) snapshots, oldest first, at most `HistoryDepth` of them
+	/// A snapshot is only taken in blocks that changed `TotalIssued`, so each one holds
+	/// until the block of the next
+	#[pallet::storage]
+	#[pallet::getter(fn supply_history)]
+	pub(super) type SupplyHistory<T: Config> =
+		StorageValue<_, BoundedVec<(T::BlockNumber, T::Balance), T::HistoryDepth>, ValueQuery>;
+
+	/// Mapping of Account -> maximum balance the account chose to cap itself at
+	#[pallet::storage]
+	#[pallet::getter(fn self_max_balance)]
+	pub(super) type SelfMaxBalance<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::Balance>;
+
+	/// Accounts allowed to hold more than `MaxBalancePerAccount`, e.g. a treasury
+	#[pallet::storage]
+	pub(super) type BalanceCapExempt<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	/// Mapping of Account -> (creator, block) for accounts created by a transfer
+	/// Only populated when `RecordAccountOrigin` is enabled
+	#[pallet::storage]
+	#[pallet::getter(fn account_origin)]
+	pub(super) type AccountOrigin<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::AccountId, T::BlockNumber)>;
+
+	/// Number of accounts currently holding a nonzero balance
+	#[pallet::storage]
+	#[pallet::getter(fn holder_count)]
+	pub(super) type HolderCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Accounts krypt holds a frame_system provider reference on
+	/// Accounts funded before krypt took provider references have none, so only references
+	/// recorded here are ever given back
+	#[pallet::storage]
+	pub(super) type ProviderRefs<T: Config> =
+		StorageMap<_, AccountHasher, T::AccountId, (), OptionQuery>;
+
+	/// Rounding applied where a rate leaves a fraction of a base unit
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub enum RoundingPolicy {
+		/// Drop the fraction, so rounding never creates funds
+		Down,
+		/// Round any fraction up to a whole base unit
+		Up,
+		/// Round to the nearest base unit, exact halves to the even neighbour
+		NearestEven,
+	}
+
+	/// Aggregate holder statistics computed off-chain
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct SupplyStatsSnapshot<Balance, BlockNumber> {
+		/// Number of accounts holding a nonzero balance
+		pub holders: u32,
+		/// Largest single balance
+		pub largest_balance: Balance,
+		/// Block whose state the statistics were computed from
+		pub block: BlockNumber,
+	}
+
+	/// Latest statistics published by the offchain worker, every `StatsInterval` blocks
+	#[pallet::storage]
+	#[pallet::getter(fn supply_stats)]
+	pub(super) type SupplyStats<T: Config> =
+		StorageValue<_, SupplyStatsSnapshot<T::Balance, T::BlockNumber>>;
+
+	/// Hasher of the `BalanceToAccount` map, `Blake2_128Concat` unless the `trusted-account-ids`
+	/// feature swaps in the cheaper `Twox64Concat`. That is only safe when account ids can't
+	/// be picked to collide, e.g. when they are public keys. Switching hashers changes every
+	/// key in the map, so it has to be decided before genesis. The `balance_read` benchmark,
+	/// run with and without the feature, shows the difference in read cost
+	#[cfg(not(feature = "trusted-account-ids"))]
+	pub type AccountHasher = Blake2_128Concat;
+	#[cfg(feature = "trusted-account-ids")]
+	pub type AccountHasher = Twox64Concat;
+
 	#[pallet::storage]
-	#[pallet::getter(fn get_balance_of)]
 	/// Mapping of Account -> Balance
+	/// Balances are stored in decay-index units, i.e. what they were worth before any decay.
+	/// Read them through `get_balance_of` to get what they are worth now
 	pub(super) type BalanceToAccount<T: Config> = StorageMap<
 		_,
-		// Remember to use a cryptographic hash function for sensitive information
+		// Blake2_128Concat unless the chain opts into trusted account ids
+		AccountHasher,
+		T::AccountId,
+		T::Balance,
+		ValueQuery,
+	>;
+
+	/// Value of the global decay index that means nothing has decayed yet
+	pub const DECAY_INDEX_ONE: u128 = 1_000_000_000_000_000_000;
+
+	#[pallet::type_value]
+	pub(super) fn DefaultDecayIndex() -> u128 {
+		DECAY_INDEX_ONE
+	}
+
+	/// Fraction of a balance still left after all decay so far, scaled by `DECAY_INDEX_ONE`
+	/// Amounts in decay-index units are worth `units * DecayIndex / DECAY_INDEX_ONE`
+	#[pallet::storage]
+	#[pallet::getter(fn decay_index)]
+	pub(super) type DecayIndex<T: Config> = StorageValue<_, u128, ValueQuery, DefaultDecayIndex>;
+
+	/// Mapping of Account frozen by the admin origin -> reason it was frozen for
+	/// Frozen accounts can still receive funds but can't move any out
+	#[pallet::storage]
+	#[pallet::getter(fn freeze_reason)]
+	pub(super) type FrozenAccounts<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<u8, T::MaxFreezeReasonLength>>;
+
+	/// Accounts no transfer may be sent to, e.g. known burn or sanctioned addresses
+	/// `force_transfer` ignores this so the admin origin can still recover funds
+	#[pallet::storage]
+	pub(super) type BlockedDestinations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	/// Accounts allowed to mint alongside `MintOrigin`, managed by `AdminOrigin`
+	#[pallet::storage]
+	pub(super) type Minters<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	/// Whether the admin origin paused all transfers
+	#[pallet::storage]
+	#[pallet::getter(fn is_paused)]
+	pub(super) type Paused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Block at which an account last received funds, either through a mint or a transfer
+	#[pallet::storage]
+	#[pallet::getter(fn last_received)]
+	pub(super) type LastReceived<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber, ValueQuery>;
+
+	/// Mapping of (Owner, Spender) -> Balance the spender may still move out of the owner's account
+	/// An allowance of `Balance::max_value()` is infinite, spending never decrements it
+	#[pallet::storage]
+	#[pallet::getter(fn allowance)]
+	pub(super) type Allowances<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
 		Blake2_128Concat,
 		T::AccountId,
 		T::Balance,
 		ValueQuery,
 	>;
 
+	/// Mapping of (Owner, Spender) -> last block at which the allowance can be spent
+	/// Allowances without an entry here never expire
+	#[pallet::storage]
+	#[pallet::getter(fn allowance_expiry)]
+	pub(super) type AllowanceExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		T::BlockNumber,
+	>;
+
+	/// Terms of an allowance that refills itself every `period` blocks
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct RecurringAllowance<Balance, BlockNumber> {
+		/// Allowance restored at the start of every period
+		pub amount: Balance,
+		/// Length of a period in blocks
+		pub period: BlockNumber,
+		/// Block at which the current period started
+		pub last_reset: BlockNumber,
+	}
+
+	/// Mapping of (Owner, Spender) -> recurring terms, for subscription style pull payments
+	#[pallet::storage]
+	#[pallet::getter(fn recurring_allowance)]
+	pub(super) type RecurringAllowances<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		RecurringAllowance<T::Balance, T::BlockNumber>,
+	>;
+
+	/// Mapping of Account -> number of permits it has signed that were used
+	/// Part of every permit payload, so each signed permit can be used only once
+	#[pallet::storage]
+	#[pallet::getter(fn permit_nonce)]
+	pub(super) type PermitNonces<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+	/// Last account visited by the idle-time reaping of dust accounts, `None` when the next
+	/// pass starts from the beginning
+	#[pallet::storage]
+	pub(super) type DustSweepCursor<T: Config> = StorageValue<_, T::AccountId>;
+
+	/// Last (owner, spender) visited by the idle-time sweep of zero-value allowances
+	/// `None` until the sweep has started
+	#[pallet::storage]
+	pub(super) type ZeroAllowanceSweepCursor<T: Config> =
+		StorageValue<_, (T::AccountId, T::AccountId)>;
+
+	/// Set once the idle-time sweep has gone over every allowance
+	/// Nothing writes zero-value allowances anymore, so there's never a need to sweep again
+	#[pallet::storage]
+	#[pallet::getter(fn zero_allowance_sweep_done)]
+	pub(super) type ZeroAllowanceSweepDone<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Every restriction currently applying to an account, as reported by `account_status`
+	/// Several can hold at once, an account with none of them set is unrestricted
+	#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct AccountStatus {
+		/// Frozen by the admin origin, can receive funds but can't move any out
+		pub frozen: bool,
+		/// On the destination blocklist, so transfers to it are rejected
+		pub blocked: bool,
+		/// The admin origin paused all transfers, so the account can't move funds out either
+		pub paused: bool,
+		/// Received funds less than `MinHoldBeforeBurn` blocks ago, so it can't burn yet
+		pub cooldown: bool,
+		/// Capped its own balance with `set_self_max_balance`, or is bound by
+		/// `MaxBalancePerAccount` without an exemption, so credits are limited
+		pub capped: bool,
+	}
+
+	impl AccountStatus {
+		/// Whether none of the restrictions apply
+		pub fn is_unrestricted(&self) -> bool {
+			self == &Self::default()
+		}
+	}
+
+	/// Funds escrowed by `offer_transfer` until the recipient accepts them
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct TransferOffer<AccountId, Balance, BlockNumber> {
+		pub from: AccountId,
+		pub to: AccountId,
+		/// Escrowed funds in decay-index units, they decay like any balance while escrowed
+		pub amount: Balance,
+		/// Block at which the offer is returned to `from` if still open
+		pub expires_at: BlockNumber,
+	}
+
+	/// Id handed out to the next transfer offer
+	#[pallet::storage]
+	#[pallet::getter(fn next_offer_id)]
+	pub(super) type NextOfferId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Mapping of offer id -> open transfer offer
+	/// Escrowed funds have left the sender's balance but are still part of `TotalIssued`
+	#[pallet::storage]
+	#[pallet::getter(fn offer)]
+	pub(super) type Offers<T: Config> =
+		StorageMap<_, Twox64Concat, u32, TransferOffer<T::AccountId, T::Balance, T::BlockNumber>>;
+
+	/// Mapping of block -> ids of offers expiring in it
+	/// Ids of offers accepted or withdrawn in the meantime are left in place and skipped
+	#[pallet::storage]
+	pub(super) type OfferExpiries<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<u32, T::MaxOffersPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			MintedThisBlock::<T>::kill();
+			let expired = Self::expire_offers(n);
+			let rate = T::DecayRatePerBlock::get();
+			let decay_weight = if rate.is_zero() || cfg!(feature = "derived-issuance") {
+				0
+			} else {
+				Self::decay(rate);
+				T::DbWeight::get().reads_writes(1, 1)
+			};
+			// Accounts for the `SupplyHistory` update in `on_finalize` too
+			T::DbWeight::get()
+				.reads_writes(3, 3)
+				.saturating_add(
+					T::DbWeight::get().reads_writes(2, 2).saturating_mul(expired as Weight),
+				)
+				.saturating_add(decay_weight)
+		}
+
+		fn on_finalize(n: T::BlockNumber) {
+			Self::record_supply_snapshot(n);
+		}
+
+		fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let used = Self::sweep_zero_allowances(remaining_weight);
+			used.saturating_add(Self::reap_dust_accounts(remaining_weight.saturating_sub(used)))
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_to_v1().saturating_add(Self::migrate_to_v2())
+		}
+
+		fn offchain_worker(n: T::BlockNumber) {
+			let interval = T::StatsInterval::get();
+			if interval.is_zero() || !(n % interval).is_zero() {
+				return
+			}
+			let stats = SupplyStatsSnapshot {
+				holders: Self::holder_count(),
+				largest_balance: Self::from_units(
+					BalanceToAccount::<T>::iter_values().max().unwrap_or_default(),
+				),
+				block: n,
+			};
+			let call = Call::submit_supply_stats { stats };
+			// A failed submission is simply retried at the next interval
+			let _ = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into());
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			ensure!(Self::on_chain_storage_version() <= 2, "Unknown krypt storage version");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(Self::on_chain_storage_version() == 2, "krypt storage wasn't migrated to v2");
+			ensure!(Self::supply_cap() >= Self::total_issued(), "krypt supply cap below issued");
+			Ok(())
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
-		/// Allow only Root to mint new tokens & transfer it to some benefactor account
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 6))]
+		/// Allow only `MintOrigin`, `AdminOrigin` or an account listed in `Minters` to mint new
+		/// tokens & transfer it to some benefactor account
 		/// Set a hard uppper limit on the total number of tokens in supply
+		/// A rejected mint is only charged for the reads its checks did
 		pub fn mint(
 			origin: OriginFor<T>,
 			#[pallet::compact] amount: <T as Config>::Balance,
 			benefactor: T::AccountId,
-		) -> DispatchResult {
-			// Check if sudo call
-			ensure_root(origin.clone())?;
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_minter(origin)?;
+			Self::do_mint(amount, benefactor)
+				.map_err(|error| error.with_weight(10_000 + T::DbWeight::get().reads(4)))?;
+			Ok(().into())
+		}
 
-			// Ensure No MaxTokenSupply or Balance type overflow
-			ensure!(
-				Self::does_adding_overflow_maxtokensupply(amount).is_ok(),
-				Error::<T>::MintCausingTotalSupplyOverflow
-			);
+		/// Mint to many benefactors at once, each leg on the same terms as `mint`
+		/// Every leg is checked before any is applied, so either all of them go through or none
+		/// do. Unlike `mint`, a batch that doesn't fit under the supply cap is never cut down
+		#[pallet::weight(
+			(10_000 + T::DbWeight::get().reads_writes(3, 4)).saturating_mul(mints.len() as Weight)
+		)]
+		pub fn mint_batch(
+			origin: OriginFor<T>,
+			mints: Vec<(T::AccountId, T::Balance)>,
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_minter(origin)?;
+			let checks_weight = 10_000 + T::DbWeight::get().reads(2 * mints.len() as Weight + 3);
+			Self::do_mint_batch(mints).map_err(|error| error.with_weight(checks_weight))?;
+			Ok(().into())
+		}
 
-			// Check if Benefactor already has funds
-			let previous_balance = <BalanceToAccount<T>>::try_get(&benefactor).unwrap_or_default();
-			let final_balance = previous_balance.saturating_add(amount);
-			<BalanceToAccount<T>>::insert(&benefactor, final_balance);
-			// Call to this helper updates `TotalIssued` storage item that tracks all minted counts in existence
-			Self::include_mint_amount(amount);
-			Self::deposit_event(Event::MintedNewSupply(amount));
+		/// Same as `mint`, but takes whole tokens, scaled up by `10^Decimals` before minting
+		/// Saves governance proposals from working out base units by hand
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 6))]
+		pub fn mint_whole(
+			origin: OriginFor<T>,
+			benefactor: T::AccountId,
+			#[pallet::compact] whole_tokens: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_minter(origin)?;
+			let amount = Self::balance_to_u128(whole_tokens)
+				.zip(10u128.checked_pow(T::Decimals::get().into()))
+				.and_then(|(whole, unit)| whole.checked_mul(unit))
+				.and_then(Self::u128_to_balance)
+				.ok_or(Error::<T>::MintTypeOverflow)?;
+			Self::do_mint(amount, benefactor)
+				.map_err(|error| error.with_weight(10_000 + T::DbWeight::get().reads(4)))?;
 			Ok(().into())
 		}
 
 		/// Transfer funds from `from` to `to`
-		#[pallet::weight(10_000)]
+		/// Unless `from` is the signer, this spends the allowance `from` gave the signer, the
+		/// same way `spend_from` does. A rejected transfer is only charged for the reads its
+		/// checks did, a successful one also emits `TransferReceipt` with the resulting
+		/// balances of both ends
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(6, 3))]
 		pub fn transfer_from(
+			origin: OriginFor<T>,
+			from: <T::Lookup as StaticLookup>::Source,
+			to: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let signer = ensure_signed(origin)?;
+			let from = T::Lookup::lookup(from)?;
+			let to = T::Lookup::lookup(to)?;
+			let result = if from == signer {
+				Self::do_transfer(from.clone(), to.clone(), amount)
+			} else {
+				Self::do_spend_from(signer, from.clone(), to.clone(), amount)
+			};
+			result.map_err(|error| error.with_weight(10_000 + T::DbWeight::get().reads(5)))?;
+			Self::deposit_event(Event::TransferReceipt {
+				from_balance: Self::get_balance_of(&from),
+				to_balance: Self::get_balance_of(&to),
+				from,
+				to,
+				amount,
+			});
+			Ok(().into())
+		}
+
+		/// Same as `transfer_from`, but fails with `KeepAlive` instead of leaving the sender
+		/// with less than `ExistentialDeposit`, or reaping it altogether
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 2))]
+		pub fn transfer_keep_alive(
 			origin: OriginFor<T>,
 			to: <T::Lookup as StaticLookup>::Source,
 			#[pallet::compact] amount: T::Balance,
 		) -> DispatchResult {
-			// Check if origin is signed and has funds
 			let sender = ensure_signed(origin)?;
-			ensure!(Self::has_sufficient_funds(&sender, amount), Error::<T>::InsufficientFunds);
 			let to = T::Lookup::lookup(to)?;
-			Self::transfer_unchecked(&sender, &to, amount).expect("Shouldn't fail");
-			Self::deposit_event(Event::TransferSuccess(sender, to, amount));
+			let remaining = Self::get_balance_of(&sender)
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::InsufficientFunds)?;
+			ensure!(remaining >= Self::keep_alive_minimum(), Error::<T>::KeepAlive);
+			Self::do_transfer(sender, to, amount)
+		}
+
+		/// Send the signer's whole balance to `to`
+		/// With `keep_alive` the signer keeps `ExistentialDeposit` so its account survives
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 2))]
+		pub fn transfer_all(
+			origin: OriginFor<T>,
+			to: <T::Lookup as StaticLookup>::Source,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			let kept = if keep_alive { Self::keep_alive_minimum() } else { Zero::zero() };
+			let amount = Self::get_balance_of(&sender).saturating_sub(kept);
+			ensure!(!amount.is_zero(), Error::<T>::InsufficientFunds);
+			Self::do_transfer(sender, to, amount)
+		}
+
+		/// Same as `transfer_from`, but tags the transfer with `memo` in the emitted event
+		/// The memo is never stored, it only lives in the `TransferWithMemo` event
+		/// Charged like `transfer_from` plus a fee per memo byte, which the event carries
+		#[pallet::weight(
+			10_000 +
+				T::DbWeight::get().reads_writes(6, 3) +
+				(memo.len() as Weight).saturating_mul(100)
+		)]
+		pub fn transfer_with_memo(
+			origin: OriginFor<T>,
+			to: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+			memo: Vec<u8>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let memo: BoundedVec<u8, T::MaxMemoLength> =
+				memo.try_into().map_err(|_| Error::<T>::MemoTooLong)?;
+			let to = T::Lookup::lookup(to)?;
+			Self::do_transfer(sender.clone(), to.clone(), amount)?;
+			Self::deposit_event(Event::TransferWithMemo { from: sender, to, amount, memo });
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn total_issuance(origin: OriginFor<T>) -> DispatchResult {
-			ensure_signed(origin)?;
-			Self::deposit_event(Event::<T>::TotalIssued(Self::total_issued()));
+		/// Transfer funds from the signer to many recipients at once
+		/// The whole batch is checked against the signer's balance before any leg is applied,
+		/// so either every leg goes through or none of them do. A rejected batch is only
+		/// charged for the reads its checks did
+		#[pallet::weight(
+			(10_000 + T::DbWeight::get().reads_writes(2, 3))
+				.saturating_mul(transfers.len() as Weight)
+		)]
+		pub fn transfer_batch(
+			origin: OriginFor<T>,
+			transfers: Vec<(<T::Lookup as StaticLookup>::Source, T::Balance)>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let checks_weight =
+				10_000 + T::DbWeight::get().reads(2 * transfers.len() as Weight + 2);
+			Self::do_transfer_batch(sender, transfers)
+				.map_err(|error| error.with_weight(checks_weight))?;
 			Ok(().into())
 		}
-	}
 
-	// Private Helper functions
-	impl<T: Config> Pallet<T> {
-		fn include_mint_amount(amount: T::Balance) {
-			// This call shouldn't go overbound because the only caller to this function is `mint` and
-			// they check for overflow errors
-			TotalIssued::<T>::put(amount.checked_add(&Self::total_issued()).expect("Cannot fail"));
+		/// Burn `amount` from the signer's balance, taking it out of circulation
+		/// If `MinHoldBeforeBurn` is set, the signer must not have received any funds within
+		/// that many blocks
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+		pub fn burn(origin: OriginFor<T>, #[pallet::compact] amount: T::Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::burn_from(&who, amount)?;
+			Self::deposit_event(Event::Burned(who, amount));
+			Ok(().into())
 		}
 
-		fn does_adding_overflow_maxtokensupply(amount: T::Balance) -> Result<(), Error<T>> {
-			let total_already_minted: T::Balance = Self::total_issued();
+		/// Voluntarily burn `amount` of the signer's funds as a public good
+		/// Tracked separately in `CommunityBurned` so community deflation events can be told
+		/// apart from other burns
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(3))]
+		pub fn donate_to_burn(
+			origin: OriginFor<T>,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::burn_from(&who, amount)?;
+			CommunityBurned::<T>::mutate(|burned| *burned = burned.saturating_add(amount));
+			Self::deposit_event(Event::CommunityBurn(who, amount));
+			Ok(().into())
+		}
 
-			let new_supply =
-				total_already_minted.checked_add(&amount).ok_or(Error::<T>::MintTypeOverflow)?;
+		/// Raise or lower the supply cap, never below what has already been issued
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn set_supply_cap(
+			origin: OriginFor<T>,
+			#[pallet::compact] new_cap: T::Balance,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(new_cap >= Self::total_issued(), Error::<T>::CapBelowIssued);
+			SupplyCap::<T>::put(new_cap);
+			Self::deposit_event(Event::SupplyCapSet(new_cap));
+			Ok(().into())
+		}
 
-			// Check that new mint doesn't exceed MaxTokenSupply
-			if new_supply <= T::MaxTokenSupply::get() {
-				Ok(())
+		/// Rescale every balance by `numerator / denominator`, rounding down, e.g. 1 / 1000 to
+		/// knock three zeros off all amounts
+		/// Open offers, allowances, self-imposed caps, the supply cap and `SupplyHistory` are
+		/// rescaled along with balances. `TotalIssued` is recomputed from the rescaled amounts
+		/// rather than scaled itself, so rounding can't make it drift from what is actually
+		/// held. Balances and offers are rescaled in the decay-index units they're stored in.
+		/// Historical counters such as `LifetimeVolume` keep their old units
+		/// Warning: this goes over every account in a single block and is only meant for
+		/// chains where that stays manageable
+		/// `holders` is a witness of `HolderCount` and `max_entries` of how many offers,
+		/// allowances, one-off and recurring, and self-imposed caps exist in total. Both set
+		/// the weight, the call fails with `WitnessTooLow` if there are more
+		#[pallet::weight(
+			10_000 +
+				T::DbWeight::get().reads_writes(6, 5) +
+				T::DbWeight::get().reads_writes(2, 2).saturating_mul((*holders).into()) +
+				T::DbWeight::get().reads_writes(1, 1).saturating_mul((*max_entries).into())
+		)]
+		pub fn redenominate(
+			origin: OriginFor<T>,
+			numerator: u32,
+			denominator: u32,
+			holders: u32,
+			max_entries: u32,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(numerator != 0 && denominator != 0, Error::<T>::InvalidRatio);
+			ensure!(Self::holder_count() <= holders, Error::<T>::WitnessTooLow);
+			let mut budget = Self::witness_budget(max_entries);
+			let offers = Self::bounded_take_with_witness(Offers::<T>::iter(), &mut budget);
+			let allowances = Self::bounded_take_with_witness(Allowances::<T>::iter(), &mut budget);
+			let recurring =
+				Self::bounded_take_with_witness(RecurringAllowances::<T>::iter(), &mut budget);
+			let self_caps =
+				Self::bounded_take_with_witness(SelfMaxBalance::<T>::iter(), &mut budget);
+			ensure!(budget > 0, Error::<T>::WitnessTooLow);
+			let rescale = |amount| Self::rescale_amount(amount, numerator, denominator);
+			// Work everything out before writing, so an overflow anywhere leaves storage as is
+			let balances = BalanceToAccount::<T>::iter()
+				.map(|(who, balance)| Ok((who, rescale(balance)?)))
+				.collect::<Result<Vec<_>, DispatchError>>()?;
+			let offers = offers
+				.into_iter()
+				.map(|(id, offer)| Ok((id, rescale(offer.amount)?)))
+				.collect::<Result<Vec<_>, DispatchError>>()?;
+			let allowances = allowances
+				.into_iter()
+				.map(|(owner, spender, amount)| {
+					Ok((owner, spender, Self::rescale_allowance(amount, &rescale)?))
+				})
+				.collect::<Result<Vec<_>, DispatchError>>()?;
+			let recurring = recurring
+				.into_iter()
+				.map(|(owner, spender, mut recurring)| {
+					recurring.amount = Self::rescale_allowance(recurring.amount, &rescale)?;
+					Ok((owner, spender, recurring))
+				})
+				.collect::<Result<Vec<_>, DispatchError>>()?;
+			let self_caps = self_caps
+				.into_iter()
+				.map(|(who, cap)| Ok((who, rescale(cap)?)))
+				.collect::<Result<Vec<_>, DispatchError>>()?;
+			// Growth rates compare snapshots with `TotalIssued`, so they move to the new units
+			let mut history = Self::supply_history();
+			for (_, issued) in history.iter_mut() {
+				*issued = rescale(*issued)?;
+			}
+			// Escrowed offers are still part of the supply
+			let total_issued = balances
+				.iter()
+				.map(|(_, balance)| *balance)
+				.chain(offers.iter().map(|(_, amount)| *amount))
+				.try_fold(T::Balance::zero(), |acc, amount| acc.checked_add(&amount))
+				.ok_or(ArithmeticError::Overflow)?;
+			// Rounded-down parts never add up to more than the rounded-down whole, so the
+			// cap can't end up below issuance. `max` just keeps that invariant explicit
+			let supply_cap = rescale(Self::supply_cap())?.max(Self::from_units(total_issued));
+			// Derived issuance equates the two, rounding included
+			let total_minted = if cfg!(feature = "derived-issuance") {
+				total_issued
 			} else {
-				Err(Error::<T>::MintCausingTotalSupplyOverflow)
+				rescale(Self::total_minted())?
+			};
+
+			// Put first, so balances rescaled down to nothing come back off it in `set_balance`
+			TotalIssued::<T>::put(total_issued);
+			balances.iter().for_each(|(who, balance)| Self::set_balance(who, *balance));
+			offers.into_iter().for_each(|(id, amount)| {
+				Offers::<T>::mutate(id, |offer| {
+					if let Some(offer) = offer {
+						offer.amount = amount;
+					}
+				})
+			});
+			allowances.into_iter().for_each(|(owner, spender, amount)| {
+				Self::write_allowance(&owner, &spender, amount)
+			});
+			recurring.into_iter().for_each(|(owner, spender, recurring)| {
+				RecurringAllowances::<T>::insert(owner, spender, recurring)
+			});
+			self_caps
+				.into_iter()
+				.for_each(|(who, cap)| SelfMaxBalance::<T>::insert(who, cap));
+			SupplyCap::<T>::put(supply_cap);
+			TotalMinted::<T>::put(total_minted);
+			SupplyHistory::<T>::put(history);
+			Self::deposit_event(Event::Redenominated { numerator, denominator });
+			Ok(().into())
+		}
+
+		/// Rescale every allowance, one-off and recurring, by `numerator / denominator`,
+		/// rounding down so no spender ends up allowed more than before in relative terms
+		/// Meant for balance migrations that don't go through `redenominate`, which already
+		/// rescales allowances itself
+		/// `max_entries` is a witness of how many allowances exist and sets the weight, the
+		/// call fails with `TooManyAllowances` if there are more
+		#[pallet::weight(
+			10_000 + T::DbWeight::get().reads_writes((*max_entries).into(), (*max_entries).into())
+		)]
+		pub fn rescale_allowances(
+			origin: OriginFor<T>,
+			numerator: u32,
+			denominator: u32,
+			max_entries: u32,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(numerator != 0 && denominator != 0, Error::<T>::InvalidRatio);
+			let rescale = |amount| Self::rescale_amount(amount, numerator, denominator);
+			let mut budget = Self::witness_budget(max_entries);
+			let allowances = Self::bounded_take_with_witness(Allowances::<T>::iter(), &mut budget);
+			let recurring =
+				Self::bounded_take_with_witness(RecurringAllowances::<T>::iter(), &mut budget);
+			ensure!(budget > 0, Error::<T>::TooManyAllowances);
+			let count = allowances.len() + recurring.len();
+			// Work everything out before writing, so an overflow leaves storage as is
+			let allowances = allowances
+				.into_iter()
+				.map(|(owner, spender, amount)| {
+					Ok((owner, spender, Self::rescale_allowance(amount, &rescale)?))
+				})
+				.collect::<Result<Vec<_>, DispatchError>>()?;
+			let recurring = recurring
+				.into_iter()
+				.map(|(owner, spender, mut recurring)| {
+					recurring.amount = Self::rescale_allowance(recurring.amount, &rescale)?;
+					Ok((owner, spender, recurring))
+				})
+				.collect::<Result<Vec<_>, DispatchError>>()?;
+
+			allowances.into_iter().for_each(|(owner, spender, amount)| {
+				Self::write_allowance(&owner, &spender, amount)
+			});
+			recurring.into_iter().for_each(|(owner, spender, recurring)| {
+				RecurringAllowances::<T>::insert(owner, spender, recurring)
+			});
+			Self::deposit_event(Event::AllowancesRescaled {
+				numerator,
+				denominator,
+				count: count as u32,
+			});
+			Ok(().into())
+		}
+
+		/// Set the token name and symbol shown by wallets and explorers
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+		pub fn set_metadata(
+			origin: OriginFor<T>,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let name: BoundedVec<u8, T::MaxMetadataLength> =
+				name.try_into().map_err(|_| Error::<T>::MetadataTooLong)?;
+			let symbol: BoundedVec<u8, T::MaxMetadataLength> =
+				symbol.try_into().map_err(|_| Error::<T>::MetadataTooLong)?;
+			TokenName::<T>::put(&name);
+			TokenSymbol::<T>::put(&symbol);
+			Self::deposit_event(Event::MetadataSet(name, symbol));
+			Ok(().into())
+		}
+
+		/// Exempt `who` from `MaxBalancePerAccount`, or make it bound by the cap again
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn set_balance_cap_exempt(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			exempt: bool,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			if exempt {
+				BalanceCapExempt::<T>::insert(&who, ());
+			} else {
+				BalanceCapExempt::<T>::remove(&who);
 			}
+			Self::deposit_event(Event::BalanceCapExemptionSet(who, exempt));
+			Ok(().into())
 		}
 
-		fn has_sufficient_funds(s: &T::AccountId, amount: T::Balance) -> bool {
-			match BalanceToAccount::<T>::try_get(&s).ok() {
-				Some(balance) if balance >= amount => true,
-				_ => false,
+		/// Cap the signer's own balance at `cap`, or lift the cap with `None`
+		/// Funds already held above the cap are left alone, only incoming credits are limited
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn set_self_max_balance(
+			origin: OriginFor<T>,
+			cap: Option<T::Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			match cap {
+				Some(cap) => SelfMaxBalance::<T>::insert(&who, cap),
+				None => SelfMaxBalance::<T>::remove(&who),
 			}
+			Self::deposit_event(Event::SelfMaxBalanceSet(who, cap));
+			Ok(().into())
 		}
 
-		fn transfer_unchecked(
-			sender: &T::AccountId,
-			to: &T::AccountId,
-			amount: T::Balance,
-		) -> Result<(), Infallible> {
-			let previous_sender_balance = Self::get_balance_of(sender);
-			// We've already performed the safety check in `has_sufficient_funds`
-			let new_sender_balance = previous_sender_balance
-				.checked_sub(&amount)
-				.expect("Never has insufficient balance though");
-			BalanceToAccount::<T>::insert(&sender, new_sender_balance);
-			BalanceToAccount::<T>::insert(&to, amount);
+		/// Allow `spender` to move up to `amount` out of the signer's account
+		/// Replaces any previous allowance, recurring, expiring or neither
+		/// Approving `Balance::max_value()` grants an infinite allowance that spending never
+		/// decrements
+		/// Approving zero removes the allowance altogether, the same as `clear_allowance`
+		/// Re-approving the plain allowance already in place is a no-op that emits nothing
+		/// and is only charged for the reads
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn approve(
+			origin: OriginFor<T>,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(owner != spender, Error::<T>::ApproveSelf);
+			if Self::allowance(&owner, &spender) == amount &&
+				!RecurringAllowances::<T>::contains_key(&owner, &spender) &&
+				!AllowanceExpiries::<T>::contains_key(&owner, &spender)
+			{
+				return Ok(Some(10_000 + T::DbWeight::get().reads(3)).into())
+			}
+			Self::write_allowance(&owner, &spender, amount);
+			RecurringAllowances::<T>::remove(&owner, &spender);
+			AllowanceExpiries::<T>::remove(&owner, &spender);
+			Self::deposit_event(Event::Approval(owner, spender, amount));
+			Ok(().into())
+		}
+
+		/// Same as `approve` on behalf of `owner`, who signed the approval off-chain so that
+		/// the signer of this call, e.g. a relayer, pays the fees instead
+		/// `signature` must be `owner`'s over the SCALE encoded
+		/// `(b"krypt/permit", genesis_hash, owner, spender, amount, nonce, deadline)`, where
+		/// `genesis_hash` is this chain's block 0 hash, so a permit can't be replayed on
+		/// another chain, and `nonce` is `permit_nonce(owner)`. The permit can't be used past
+		/// block `deadline`
+		#[pallet::weight(100_000 + T::DbWeight::get().reads_writes(2, 4))]
+		pub fn permit(
+			origin: OriginFor<T>,
+			owner: T::AccountId,
+			spender: T::AccountId,
+			#[pallet::compact] amount: T::Balance,
+			deadline: T::BlockNumber,
+			signature: T::Signature,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= deadline,
+				Error::<T>::PermitExpired
+			);
+			ensure!(owner != spender, Error::<T>::ApproveSelf);
+			let nonce = Self::permit_nonce(&owner);
+			let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+			let signed = (b"krypt/permit", genesis_hash, &owner, &spender, amount, nonce, deadline)
+				.using_encoded(|payload| signature.verify(payload, &owner));
+			ensure!(signed, Error::<T>::InvalidPermitSignature);
+			PermitNonces::<T>::insert(&owner, nonce.saturating_add(1));
+			Self::write_allowance(&owner, &spender, amount);
+			RecurringAllowances::<T>::remove(&owner, &spender);
+			AllowanceExpiries::<T>::remove(&owner, &spender);
+			Self::deposit_event(Event::Approval(owner, spender, amount));
+			Ok(().into())
+		}
+
+		/// Revoke whatever allowance `spender` has on the signer's account
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(3))]
+		pub fn clear_allowance(
+			origin: OriginFor<T>,
+			spender: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			Allowances::<T>::remove(&owner, &spender);
+			RecurringAllowances::<T>::remove(&owner, &spender);
+			AllowanceExpiries::<T>::remove(&owner, &spender);
+			Self::deposit_event(Event::Approval(owner, spender, Zero::zero()));
+			Ok(().into())
+		}
+
+		/// Same as `approve`, but the allowance can't be spent after block `expiry`
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(3))]
+		pub fn approve_with_expiry(
+			origin: OriginFor<T>,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+			expiry: T::BlockNumber,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(owner != spender, Error::<T>::ApproveSelf);
+			Self::write_allowance(&owner, &spender, amount);
+			RecurringAllowances::<T>::remove(&owner, &spender);
+			if amount.is_zero() {
+				AllowanceExpiries::<T>::remove(&owner, &spender);
+			} else {
+				AllowanceExpiries::<T>::insert(&owner, &spender, expiry);
+			}
+			Self::deposit_event(Event::ExpiringApproval(owner, spender, amount, expiry));
+			Ok(().into())
+		}
+
+		/// Allow `spender` to move up to `amount` out of the signer's account every `period`
+		/// blocks. Unspent allowance doesn't carry over, it is reset to `amount` lazily the
+		/// first time the spender uses it in a new period
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+		pub fn approve_recurring(
+			origin: OriginFor<T>,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+			period: T::BlockNumber,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			ensure!(!period.is_zero(), Error::<T>::ZeroPeriod);
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(owner != spender, Error::<T>::ApproveSelf);
+			let now = frame_system::Pallet::<T>::block_number();
+			Self::write_allowance(&owner, &spender, amount);
+			AllowanceExpiries::<T>::remove(&owner, &spender);
+			RecurringAllowances::<T>::insert(
+				&owner,
+				&spender,
+				RecurringAllowance { amount, period, last_reset: now },
+			);
+			Self::deposit_event(Event::RecurringApproval(owner, spender, amount, period));
+			Ok(().into())
+		}
+
+		/// Move `amount` out of `owner`'s account into `to`, spending the signer's allowance
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(3))]
+		pub fn spend_from(
+			origin: OriginFor<T>,
+			owner: <T::Lookup as StaticLookup>::Source,
+			to: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResult {
+			let spender = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let to = T::Lookup::lookup(to)?;
+			Self::do_spend_from(spender, owner, to, amount)
+		}
+
+		/// Escrow `amount` of the signer's funds for `to`, who has to `accept_transfer` it
+		/// Until then the signer can `withdraw_offer` it, and after `OfferExpiry` blocks it
+		/// goes back to the signer on its own
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(4))]
+		pub fn offer_transfer(
+			origin: OriginFor<T>,
+			to: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			Self::ensure_transfer_allowed(&from, &to, amount)?;
+			let units = Self::to_units(amount).ok_or(ArithmeticError::Overflow)?;
+			let new_balance = BalanceToAccount::<T>::get(&from)
+				.checked_sub(&units)
+				.ok_or(Error::<T>::InsufficientFunds)?;
+			// Nothing to escrow, same as a transfer to oneself
+			if from == to {
+				return Ok(().into())
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			let expires_at = now.saturating_add(T::OfferExpiry::get().max(One::one()));
+			let id = Self::next_offer_id();
+			let next_id = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+			OfferExpiries::<T>::try_mutate(expires_at, |ids| ids.try_push(id))
+				.map_err(|_| Error::<T>::TooManyOffers)?;
+			Self::set_balance(&from, new_balance);
+			Offers::<T>::insert(
+				id,
+				TransferOffer { from: from.clone(), to: to.clone(), amount: units, expires_at },
+			);
+			NextOfferId::<T>::put(next_id);
+			Self::deposit_event(Event::TransferOffered(id, from, to, amount));
+			Ok(().into())
+		}
+
+		/// Accept an open offer addressed to the signer, receiving its funds
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(3))]
+		pub fn accept_transfer(origin: OriginFor<T>, id: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let offer = Self::offer(id).ok_or(Error::<T>::OfferNotFound)?;
+			ensure!(offer.to == who, Error::<T>::NotOfferRecipient);
+			// The recipient may have been blocked since the offer was made
+			Self::ensure_destination_allowed(&who)?;
+			let (fee, received) = Self::split_fee(Self::from_units(offer.amount))?;
+			Self::ensure_can_receive(&who, received)?;
+			Offers::<T>::remove(id);
+			Self::settle_transfer(&offer.from, &who, offer.amount, fee, received);
+			Self::deposit_event(Event::TransferAccepted(id));
+			#[cfg(debug_assertions)]
+			Self::assert_issuance_consistent();
+			Ok(().into())
+		}
+
+		/// Take back an offer the signer made that hasn't been accepted yet
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+		pub fn withdraw_offer(origin: OriginFor<T>, id: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let offer = Self::offer(id).ok_or(Error::<T>::OfferNotFound)?;
+			ensure!(offer.from == who, Error::<T>::NotOfferSender);
+			Offers::<T>::remove(id);
+			Self::refund_offer(&offer);
+			Self::deposit_event(Event::OfferWithdrawn(id));
+			Ok(().into())
+		}
+
+		/// Store statistics computed by the offchain worker
+		/// Only accepted as an unsigned transaction coming from the local node, see
+		/// `validate_unsigned`
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn submit_supply_stats(
+			origin: OriginFor<T>,
+			stats: SupplyStatsSnapshot<T::Balance, T::BlockNumber>,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			SupplyStats::<T>::put(&stats);
+			Self::deposit_event(Event::SupplyStatsUpdated(stats));
+			Ok(().into())
+		}
+
+		/// Emits `TotalIssued` so the current supply shows up in the event log
+		/// Clients reading the supply should use the `total_issuance_krypt_api` runtime API or
+		/// the `TotalIssued` storage item instead of submitting a transaction
+		/// Kept callable so existing clients don't break
+		#[deprecated(note = "Read the supply through the `total_issuance_krypt_api` runtime API")]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn total_issuance(origin: OriginFor<T>) -> DispatchResult {
+			ensure_signed(origin)?;
+			let total_issued = Self::total_issued();
+			if T::SuppressRedundantSnapshots::get() &&
+				Self::last_reported_issuance() == Some(total_issued)
+			{
+				return Ok(().into())
+			}
+			LastReportedIssuance::<T>::put(total_issued);
+			Self::deposit_event(Event::<T>::TotalIssued(total_issued));
+			Ok(().into())
+		}
+
+		/// Stop `who` from moving funds out of its account, recording `reason` for auditors
+		/// Freezing an already frozen account replaces the reason
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn freeze(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			reason: Vec<u8>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			let reason: BoundedVec<u8, T::MaxFreezeReasonLength> =
+				reason.try_into().map_err(|_| Error::<T>::FreezeReasonTooLong)?;
+			FrozenAccounts::<T>::insert(&who, &reason);
+			Self::deposit_event(Event::AccountFrozen(who, reason));
+			Ok(().into())
+		}
+
+		/// Lift the freeze on `who`
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn thaw(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			FrozenAccounts::<T>::take(&who).ok_or(Error::<T>::NotFrozen)?;
+			Self::deposit_event(Event::AccountThawed(who));
+			Ok(().into())
+		}
+
+		/// Remove everything this pallet stores about `who`, for compliance driven removals
+		/// Its whole balance is burned and taken out of `TotalIssued`, and its allowances as
+		/// owner and as spender are dropped along with its cap, freeze and history entries.
+		/// The destination blocklist is policy rather than account data and is left as is,
+		/// and open offers from `who` still refund to it when they expire
+		/// Finding the allowances `who` holds as a spender means going over every allowance,
+		/// so `max_entries` is a witness of how many exist and sets the weight, the call fails
+		/// with `TooManyAllowances` if there are more
+		#[pallet::weight(
+			10_000 +
+				T::DbWeight::get().reads_writes(
+					(*max_entries).saturating_add(10).into(),
+					(*max_entries).saturating_add(10).into(),
+				)
+		)]
+		pub fn wipe_account(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			max_entries: u32,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			let units = BalanceToAccount::<T>::get(&who);
+			let burned = Self::from_units(units);
+			Self::ensure_can_burn(units)?;
+			let mut budget = Self::witness_budget(max_entries);
+			let allowances =
+				Self::bounded_take_with_witness(Allowances::<T>::iter_keys(), &mut budget);
+			let recurring =
+				Self::bounded_take_with_witness(RecurringAllowances::<T>::iter_keys(), &mut budget);
+			ensure!(budget > 0, Error::<T>::TooManyAllowances);
+
+			Self::set_balance(&who, Zero::zero());
+			TotalIssued::<T>::mutate(|total| *total = total.saturating_sub(units));
+			let _ = Allowances::<T>::remove_prefix(&who, None);
+			let _ = AllowanceExpiries::<T>::remove_prefix(&who, None);
+			let _ = RecurringAllowances::<T>::remove_prefix(&who, None);
+			allowances
+				.into_iter()
+				.chain(recurring)
+				.filter(|(_, spender)| *spender == who)
+				.for_each(|(owner, spender)| {
+					Allowances::<T>::remove(&owner, &spender);
+					AllowanceExpiries::<T>::remove(&owner, &spender);
+					RecurringAllowances::<T>::remove(&owner, &spender);
+				});
+			SelfMaxBalance::<T>::remove(&who);
+			FrozenAccounts::<T>::remove(&who);
+			BalanceCapExempt::<T>::remove(&who);
+			AccountOrigin::<T>::remove(&who);
+			LastReceived::<T>::remove(&who);
+			Self::deposit_event(Event::AccountWiped { who, burned });
+			#[cfg(debug_assertions)]
+			Self::assert_issuance_consistent();
+			Ok(().into())
+		}
+
+		/// Reject every transfer to `who` from now on
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn block_destination(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			BlockedDestinations::<T>::insert(&who, ());
+			Self::deposit_event(Event::DestinationBlocked(who));
+			Ok(().into())
+		}
+
+		/// Allow transfers to `who` again
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn unblock_destination(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			BlockedDestinations::<T>::remove(&who);
+			Self::deposit_event(Event::DestinationUnblocked(who));
+			Ok(().into())
+		}
+
+		/// Let `who` mint with a plain signed origin, without going through `MintOrigin`
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn add_minter(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			Minters::<T>::insert(&who, ());
+			Self::deposit_event(Event::MinterAdded(who));
+			Ok(().into())
+		}
+
+		/// Take away the right to mint given to `who` by `add_minter`
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn remove_minter(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			Minters::<T>::take(&who).ok_or(Error::<T>::NotMinter)?;
+			Self::deposit_event(Event::MinterRemoved(who));
+			Ok(().into())
+		}
+
+		/// Move `amount` from `from` to `to` on behalf of the admin origin, e.g. to recover
+		/// funds. Pauses, freezes, the destination blocklist and `MinTransferAmount` don't
+		/// apply, the transfer fee and balance caps still do
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 2))]
+		pub fn force_transfer(
+			origin: OriginFor<T>,
+			from: <T::Lookup as StaticLookup>::Source,
+			to: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let from = T::Lookup::lookup(from)?;
+			let to = T::Lookup::lookup(to)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			ensure!(Self::has_sufficient_funds(&from, amount), Error::<T>::InsufficientFunds);
+			if from == to {
+				return Ok(().into())
+			}
+			Self::transfer_unchecked(&from, &to, amount)?;
+			Self::deposit_event(Event::ForceTransfer(from, to, amount));
+			#[cfg(debug_assertions)]
+			Self::assert_issuance_consistent();
+			Ok(().into())
+		}
+
+		/// Pause or resume transfers out of every account
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn set_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Paused::<T>::put(paused);
+			Self::deposit_event(Event::PauseSet(paused));
+			Ok(().into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Only statistics produced by this node's own offchain worker are accepted into the
+		/// pool, and only if they are newer than what is already stored
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if source != TransactionSource::Local {
+				return InvalidTransaction::Call.into()
+			}
+			let stats = Self::fresh_supply_stats(call)?;
+			ValidTransaction::with_tag_prefix("KryptSupplyStats")
+				.priority(TransactionPriority::max_value())
+				.and_provides(stats.block)
+				.longevity(T::StatsInterval::get().saturated_into::<u64>())
+				.propagate(false)
+				.build()
+		}
+
+		/// Runs when a block is built or imported, where the source check above doesn't apply
+		/// The stats are never gossiped, so they only ever come from the block author's own
+		/// pool, and only stale ones are turned away
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			Self::fresh_supply_stats(call).map(|_| ())
+		}
+	}
+
+	/// Supply created by `issue` that doesn't have an owner yet
+	/// Hand it to `deposit_issued` to credit an account with it. Dropping it instead takes the
+	/// amount back out of `TotalIssued`, as if it had never been issued
+	#[must_use]
+	pub struct Issued<T: Config> {
+		amount: T::Balance,
+		/// `amount` in decay-index units as of when it was issued
+		units: T::Balance,
+	}
+
+	impl<T: Config> Issued<T> {
+		/// Amount of supply behind this issuance
+		pub fn peek(&self) -> T::Balance {
+			self.amount
+		}
+	}
+
+	impl<T: Config> Drop for Issued<T> {
+		fn drop(&mut self) {
+			TotalMinted::<T>::mutate(|minted| *minted = minted.saturating_sub(self.amount));
+			TotalIssued::<T>::mutate(|total| *total = total.saturating_sub(self.units));
+		}
+	}
+
+	// Issuance for other pallets, e.g. block rewards that are routed to an owner later
+	impl<T: Config> Pallet<T> {
+		/// Issue `amount` of new supply without an owner, within the supply cap and
+		/// `MintRatePerBlock`
+		pub fn issue(amount: T::Balance) -> Result<Issued<T>, DispatchError> {
+			Self::ensure_min_mint(amount)?;
+			Self::does_adding_overflow_maxtokensupply(amount)?;
+			let units = Self::to_units(amount).ok_or(ArithmeticError::Overflow)?;
+			Self::include_in_block_mint_rate(amount)?;
+			Self::include_mint_amount(amount, units);
+			Ok(Issued { amount, units })
+		}
+
+		/// Credit `who` with issued supply
+		/// The issuance is handed back if `who` can't receive it, because of its self-imposed
+		/// cap, `MaxBalancePerAccount` or because its balance would overflow
+		pub fn deposit_issued(who: &T::AccountId, issued: Issued<T>) -> Result<(), Issued<T>> {
+			let amount = issued.peek();
+			if BalanceToAccount::<T>::get(who).checked_add(&issued.units).is_none() ||
+				Self::ensure_can_receive(who, amount).is_err()
+			{
+				return Err(issued)
+			}
+			Self::credit(who, issued.units);
+			Self::note_received(who);
+			// The supply now has an owner, so it must stay in `TotalIssued`
+			sp_std::mem::forget(issued);
+			Self::deposit_event(Event::MintedNewSupply {
+				to: who.clone(),
+				amount,
+				total_issued: Self::total_issued(),
+			});
+			Ok(())
+		}
+	}
+
+	// Public read-only helpers, meant to be called through runtime APIs rather than extrinsics
+	impl<T: Config> Pallet<T> {
+		/// Balance of `who`, with everything that decayed since it was written taken off
+		pub fn get_balance_of(who: &T::AccountId) -> T::Balance {
+			Self::from_units(BalanceToAccount::<T>::get(who))
+		}
+
+		/// Supply in circulation, with everything that decayed so far taken off
+		/// Each balance rounds down on its own, so their sum can be a few base units below this
+		pub fn total_issued() -> T::Balance {
+			Self::from_units(TotalIssued::<T>::get())
+		}
+
+		/// Whether the admin origin froze `who`
+		pub fn is_frozen(who: &T::AccountId) -> bool {
+			FrozenAccounts::<T>::contains_key(who)
+		}
+
+		/// Every restriction on `who` in one go, so wallets don't have to query each of them
+		pub fn account_status(who: &T::AccountId) -> AccountStatus {
+			AccountStatus {
+				frozen: Self::is_frozen(who),
+				blocked: BlockedDestinations::<T>::contains_key(who),
+				paused: Self::is_paused(),
+				cooldown: !Self::held_long_enough(who),
+				capped: Self::self_max_balance(who).is_some() ||
+					(T::MaxBalancePerAccount::get().is_some() &&
+						!BalanceCapExempt::<T>::contains_key(who)),
+			}
+		}
+
+		/// Whether `who` holds a balance, accounts are reaped as soon as it hits zero
+		/// Wallets use this to tell transfers that create an account from ones that don't
+		pub fn account_exists(who: &T::AccountId) -> bool {
+			BalanceToAccount::<T>::contains_key(who)
+		}
+
+		/// Raw growth of `TotalIssued` over the last `window_blocks` blocks, compared against
+		/// the most recent `SupplyHistory` snapshot taken at least that many blocks ago
+		/// Zero if there's no such snapshot, the snapshot saw no supply, or supply shrank
+		/// Growth beyond 100% saturates at `Perbill::one()`
+		pub fn inflation_rate(window_blocks: T::BlockNumber) -> Perbill {
+			let now = frame_system::Pallet::<T>::block_number();
+			let target = match now.checked_sub(&window_blocks) {
+				Some(target) => target,
+				None => return Perbill::zero(),
+			};
+			let past_issued =
+				match Self::supply_history().iter().rev().find(|(block, _)| *block <= target) {
+					Some((_, issued)) => *issued,
+					None => return Perbill::zero(),
+				};
+			let current_issued = Self::total_issued();
+			if past_issued.is_zero() || current_issued <= past_issued {
+				return Perbill::zero()
+			}
+			Perbill::from_rational(current_issued - past_issued, past_issued)
+		}
+
+		/// Up to `limit` holders, at most `MaxAccountsPage`, starting right after the raw storage
+		/// key `start_key`, or from the beginning without one
+		/// Also returns the key to pass in for the next page, `None` once every holder has been
+		/// returned. Meant for offchain tooling such as airdrop scripts walking all holders
+		pub fn accounts_page(
+			start_key: Option<Vec<u8>>,
+			limit: u32,
+		) -> (Vec<(T::AccountId, T::Balance)>, Option<Vec<u8>>) {
+			let limit = limit.min(T::MaxAccountsPage::get()) as usize;
+			let holders = match start_key {
+				Some(key) => BalanceToAccount::<T>::iter_from(key),
+				None => BalanceToAccount::<T>::iter(),
+			};
+			let page = holders
+				.take(limit)
+				.map(|(who, units)| (who, Self::from_units(units)))
+				.collect::<Vec<_>>();
+			let next_key = if page.len() == limit {
+				page.last().map(|(who, _)| BalanceToAccount::<T>::hashed_key_for(who))
+			} else {
+				None
+			};
+			(page, next_key)
+		}
+
+		/// Median of all account balances, zero if there are no accounts
+		/// For an even number of accounts this is the mean of the two middle balances,
+		/// rounded down
+		/// Note: This reads and sorts every entry in `BalanceToAccount`, so memory and compute
+		/// grow as O(n) and O(n log n) with the number of accounts. Never call it on-chain
+		pub fn median_balance() -> T::Balance {
+			let mut balances = BalanceToAccount::<T>::iter_values().collect::<Vec<_>>();
+			if balances.is_empty() {
+				return Zero::zero()
+			}
+			balances.sort_unstable();
+			let mid = balances.len() / 2;
+			if balances.len() % 2 == 1 {
+				Self::from_units(balances[mid])
+			} else {
+				let (lower, upper) =
+					(Self::from_units(balances[mid - 1]), Self::from_units(balances[mid]));
+				// upper >= lower since balances are sorted, so this can't overflow
+				lower + (upper - lower) / 2u32.into()
+			}
+		}
+	}
+
+	// Fixture helpers for integration tests
+	#[cfg(feature = "std")]
+	impl<T: Config> Pallet<T> {
+		/// Replace all balances and allowances and set `TotalIssued` in one go, bypassing
+		/// every extrinsic check
+		/// Recurring and expiring allowances and open offers are dropped, and `TotalMinted` is
+		/// set to `total_issued` as if all of it had been minted, so derived issuance agrees
+		/// Panics if `total_issued` isn't the sum of `balances`
+		pub fn set_test_state(
+			balances: Vec<(T::AccountId, T::Balance)>,
+			allowances: Vec<(T::AccountId, T::AccountId, T::Balance)>,
+			total_issued: T::Balance,
+		) {
+			let sum = balances.iter().fold(T::Balance::zero(), |acc, (_, balance)| {
+				acc.checked_add(balance).expect("Test balances overflow the Balance type")
+			});
+			assert_eq!(sum, total_issued, "total_issued must equal the sum of all balances");
+			let balances = balances
+				.into_iter()
+				.map(|(who, balance)| {
+					(who, Self::to_units(balance).expect("Test balances overflow decay units"))
+				})
+				.collect::<Vec<_>>();
+			let total_units = balances.iter().fold(T::Balance::zero(), |acc, (_, units)| {
+				acc.checked_add(units).expect("Test balances overflow decay units")
+			});
+
+			// Go through `set_balance` so provider references stay consistent
+			BalanceToAccount::<T>::iter_keys()
+				.collect::<Vec<_>>()
+				.iter()
+				.for_each(|who| Self::set_balance(who, Zero::zero()));
+			let _ = Allowances::<T>::remove_all(None);
+			let _ = AllowanceExpiries::<T>::remove_all(None);
+			let _ = RecurringAllowances::<T>::remove_all(None);
+			let _ = Offers::<T>::remove_all(None);
+			let _ = OfferExpiries::<T>::remove_all(None);
+			balances.iter().for_each(|(who, balance)| Self::set_balance(who, *balance));
+			allowances.into_iter().for_each(|(owner, spender, amount)| {
+				Self::write_allowance(&owner, &spender, amount)
+			});
+			TotalMinted::<T>::put(total_issued);
+			TotalIssued::<T>::put(total_units);
+		}
+	}
+
+	// Decimal formatting helpers for frontends and CLI tools
+	#[cfg(feature = "std")]
+	impl<T: Config> Pallet<T> {
+		/// Render a raw balance using `Decimals`, trimming trailing zeros from the fraction
+		/// e.g. 1_500_000 with 6 decimals renders as "1.5" and 2_000_000 as "2"
+		pub fn format_balance(amount: T::Balance) -> String {
+			let decimals = T::Decimals::get() as usize;
+			let raw = format!("{:0>width$}", amount.saturated_into::<u128>(), width = decimals + 1);
+			let (whole, fraction) = raw.split_at(raw.len() - decimals);
+			let fraction = fraction.trim_end_matches('0');
+			if fraction.is_empty() {
+				whole.to_string()
+			} else {
+				format!("{}.{}", whole, fraction)
+			}
+		}
+
+		/// Inverse of `format_balance`, turning e.g. "1.5" into 1_500_000 with 6 decimals
+		pub fn parse_balance(s: &str) -> Result<T::Balance, &'static str> {
+			let decimals = T::Decimals::get() as usize;
+			let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+			if whole.is_empty() && fraction.is_empty() {
+				return Err("Empty balance")
+			}
+			if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+				return Err("Balance must only contain digits and a decimal point")
+			}
+			if fraction.len() > decimals {
+				return Err("Balance has more decimal places than the token")
+			}
+			let raw = format!("{}{:0<width$}", whole, fraction, width = decimals)
+				.parse::<u128>()
+				.map_err(|_| "Balance out of range")?;
+			T::Balance::try_from(raw).map_err(|_| "Balance out of range")
+		}
+	}
+
+	// Conversions for cross-pallet and XCM code that deals in plain u128 amounts
+	impl<T: Config> Pallet<T> {
+		/// Widen `b` to a `u128`, `None` if it doesn't fit
+		pub fn balance_to_u128(b: T::Balance) -> Option<u128> {
+			b.try_into().ok()
+		}
+
+		/// Narrow `v` to a `Balance`, `None` if it doesn't fit rather than saturating
+		pub fn u128_to_balance(v: u128) -> Option<T::Balance> {
+			v.try_into().ok()
+		}
+	}
+
+	// Private Helper functions
+	impl<T: Config> Pallet<T> {
+		/// `units` is `amount` in decay-index units
+		fn include_mint_amount(amount: T::Balance, units: T::Balance) {
+			// This call shouldn't go overbound because the only caller to this function is `mint`
+			// and they check for overflow errors
+			TotalMinted::<T>::put(amount.checked_add(&Self::total_minted()).expect("Cannot fail"));
+			#[cfg(not(feature = "derived-issuance"))]
+			TotalIssued::<T>::put(
+				units.checked_add(&TotalIssued::<T>::get()).expect("Cannot fail"),
+			);
+			// Genesis endowments count as minted too, so everything in circulation was minted
+			#[cfg(feature = "derived-issuance")]
+			TotalIssued::<T>::put(Self::total_minted());
+		}
+
+		/// Count `amount` towards this block's minted total, failing if that goes over
+		/// `MintRatePerBlock`
+		fn include_in_block_mint_rate(amount: T::Balance) -> DispatchResult {
+			let minted_this_block = Self::minted_this_block()
+				.checked_add(&amount)
+				.ok_or(ArithmeticError::Overflow)?;
+			let limit = T::MintRatePerBlock::get();
+			ensure!(limit.is_zero() || minted_this_block <= limit, Error::<T>::MintRateExceeded);
+			MintedThisBlock::<T>::put(minted_this_block);
+			Ok(())
+		}
+
+		fn does_adding_overflow_maxtokensupply(amount: T::Balance) -> Result<(), Error<T>> {
+			let total_already_minted: T::Balance = TotalIssued::<T>::get();
+
+			// Worked out in decay-index units, the way `TotalIssued` will actually grow
+			let new_supply = Self::to_units(amount)
+				.and_then(|units| total_already_minted.checked_add(&units))
+				.map(Self::from_units)
+				.ok_or(Error::<T>::MintTypeOverflow)?;
+
+			// Check that new mint doesn't exceed the current supply cap
+			if new_supply <= Self::supply_cap() {
+				Ok(())
+			} else {
+				Err(Error::<T>::MintCausingTotalSupplyOverflow)
+			}
+		}
+
+		/// Accept `MintOrigin`, `AdminOrigin` or a signed origin listed in `Minters`
+		fn ensure_minter(origin: OriginFor<T>) -> DispatchResult {
+			let origin = match T::MintOrigin::try_origin(origin) {
+				Ok(_) => return Ok(()),
+				Err(origin) => origin,
+			};
+			let origin = match T::AdminOrigin::try_origin(origin) {
+				Ok(_) => return Ok(()),
+				Err(origin) => origin,
+			};
+			let who = ensure_signed(origin)?;
+			ensure!(Minters::<T>::contains_key(&who), DispatchError::BadOrigin);
+			Ok(())
+		}
+
+		/// Checks every transfer path shares, apart from the sender's funds
+		/// A transfer to oneself goes through them all the same, and then moves nothing
+		fn ensure_transfer_allowed(
+			from: &T::AccountId,
+			to: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			ensure!(amount >= T::MinTransferAmount::get(), Error::<T>::BelowMinTransfer);
+			Self::ensure_can_send(from)?;
+			Self::ensure_destination_allowed(to)
+		}
+
+		/// Fails unless `who` may move funds out of its account
+		fn ensure_can_send(who: &T::AccountId) -> DispatchResult {
+			ensure!(!Self::is_paused(), Error::<T>::TransfersPaused);
+			ensure!(!Self::is_frozen(who), Error::<T>::AccountFrozen);
+			Ok(())
+		}
+
+		/// Fails if `to` is on the destination blocklist
+		fn ensure_destination_allowed(to: &T::AccountId) -> DispatchResult {
+			ensure!(!BlockedDestinations::<T>::contains_key(to), Error::<T>::DestinationBlocked);
+			Ok(())
+		}
+
+		/// Statistics carried by `call`, as long as they are newer than what is already stored
+		fn fresh_supply_stats(
+			call: &Call<T>,
+		) -> Result<&SupplyStatsSnapshot<T::Balance, T::BlockNumber>, TransactionValidityError> {
+			let stats = match call {
+				Call::submit_supply_stats { stats } => stats,
+				_ => return Err(InvalidTransaction::Call.into()),
+			};
+			if Self::supply_stats().map_or(false, |current| current.block >= stats.block) {
+				return Err(InvalidTransaction::Stale.into())
+			}
+			Ok(stats)
+		}
+
+		/// Set the allowance `spender` has on `owner`'s account, removing the entry rather than
+		/// storing a zero, which reads the same
+		fn write_allowance(owner: &T::AccountId, spender: &T::AccountId, amount: T::Balance) {
+			if amount.is_zero() {
+				Allowances::<T>::remove(owner, spender);
+			} else {
+				Allowances::<T>::insert(owner, spender, amount);
+			}
+		}
+
+		/// Restore a recurring allowance to its full amount if its period has elapsed
+		fn refresh_allowance(owner: &T::AccountId, spender: &T::AccountId) {
+			RecurringAllowances::<T>::mutate(owner, spender, |maybe_terms| {
+				if let Some(terms) = maybe_terms {
+					let now = frame_system::Pallet::<T>::block_number();
+					if now >= terms.last_reset.saturating_add(terms.period) {
+						// Align to the latest period boundary so skipped periods don't stack up
+						let elapsed = now - terms.last_reset;
+						terms.last_reset = now - elapsed % terms.period;
+						Self::write_allowance(owner, spender, terms.amount);
+					}
+				}
+			});
+		}
+
+		/// Remove as many zero-value `Allowances` entries as `budget` allows, picking up where
+		/// the previous call left off. Such entries were left behind by older versions, while
+		/// every path now removes an allowance instead of storing a zero, so this is a one-off
+		/// migration: once a pass has gone over every allowance `ZeroAllowanceSweepDone` is
+		/// set and later calls only read that flag
+		/// Returns the weight used, never more than `budget`
+		fn sweep_zero_allowances(budget: Weight) -> Weight {
+			let overhead = T::DbWeight::get().reads_writes(2, 2);
+			let per_entry = T::DbWeight::get().reads_writes(1, 2);
+			if budget < overhead.saturating_add(per_entry) {
+				return 0
+			}
+			if Self::zero_allowance_sweep_done() {
+				return T::DbWeight::get().reads(1)
+			}
+			// Only a runtime that weighs storage access at zero gets an unbounded sweep
+			let max_entries = (budget - overhead).checked_div(per_entry).unwrap_or(Weight::MAX);
+			let mut entries = match ZeroAllowanceSweepCursor::<T>::get() {
+				Some((owner, spender)) =>
+					Allowances::<T>::iter_from(Allowances::<T>::hashed_key_for(&owner, &spender)),
+				None => Allowances::<T>::iter(),
+			};
+			let mut visited: Weight = 0;
+			let mut last = None;
+			while visited < max_entries {
+				match entries.next() {
+					Some((owner, spender, amount)) => {
+						visited += 1;
+						// Removing the entry just visited doesn't disturb the iteration
+						if amount.is_zero() {
+							Allowances::<T>::remove(&owner, &spender);
+							AllowanceExpiries::<T>::remove(&owner, &spender);
+						}
+						last = Some((owner, spender));
+					},
+					None => {
+						ZeroAllowanceSweepDone::<T>::put(true);
+						last = None;
+						break
+					},
+				}
+			}
+			ZeroAllowanceSweepCursor::<T>::set(last);
+			overhead.saturating_add(per_entry.saturating_mul(visited))
+		}
+
+		/// Burn the balance of accounts holding less than `ExistentialDeposit`, visiting as
+		/// many accounts as `budget` allows and picking up where the previous call left off
+		/// Once the end of `BalanceToAccount` is reached the next call starts over, so dust
+		/// left by decay is caught too. Transfers are kept cheap by not reaping inline
+		/// Does nothing with derived issuance, where burning isn't possible
+		/// Returns the weight used, never more than `budget`
+		fn reap_dust_accounts(budget: Weight) -> Weight {
+			let overhead = T::DbWeight::get().reads_writes(1, 1);
+			let per_entry = T::DbWeight::get().reads_writes(3, 5);
+			let existential_deposit = T::ExistentialDeposit::get();
+			if existential_deposit.is_zero() ||
+				cfg!(feature = "derived-issuance") ||
+				budget < overhead.saturating_add(per_entry)
+			{
+				return 0
+			}
+			// Only a runtime that weighs storage access at zero gets an unbounded sweep
+			let max_entries = (budget - overhead).checked_div(per_entry).unwrap_or(Weight::MAX);
+			let mut accounts = match DustSweepCursor::<T>::get() {
+				Some(who) =>
+					BalanceToAccount::<T>::iter_from(BalanceToAccount::<T>::hashed_key_for(&who)),
+				None => BalanceToAccount::<T>::iter(),
+			};
+			let mut visited: Weight = 0;
+			let mut last = None;
+			while visited < max_entries {
+				match accounts.next() {
+					Some((who, stored)) => {
+						visited += 1;
+						let balance = Self::from_units(stored);
+						// Removing the account just visited doesn't disturb the iteration
+						if !balance.is_zero() && balance < existential_deposit {
+							Self::set_balance(&who, Zero::zero());
+							TotalIssued::<T>::mutate(|total| *total = total.saturating_sub(stored));
+							Self::deposit_event(Event::DustReaped {
+								who: who.clone(),
+								amount: balance,
+							});
+						}
+						last = Some(who);
+					},
+					None => {
+						last = None;
+						break
+					},
+				}
+			}
+			DustSweepCursor::<T>::set(last);
+			overhead.saturating_add(per_entry.saturating_mul(visited))
+		}
+
+		/// Append this block's `TotalIssued` to `SupplyHistory` if it changed since the last
+		/// snapshot, evicting the oldest snapshot once `HistoryDepth` is reached
+		fn record_supply_snapshot(now: T::BlockNumber) {
+			let issued = Self::total_issued();
+			SupplyHistory::<T>::mutate(|history| {
+				if history.last().map_or(false, |(_, last)| *last == issued) {
+					return
+				}
+				if history.len() as u32 >= T::HistoryDepth::get() && !history.is_empty() {
+					history.remove(0);
+				}
+				// Can only fail if `HistoryDepth` is zero, in which case nothing is kept
+				let _ = history.try_push((now, issued));
+			});
+		}
+
+		/// v0 -> v1: `SupplyCap` used to be the `MaxTokenSupply` constant, so chains upgrading
+		/// from v0 get it written to storage explicitly
+		fn migrate_to_v1() -> Weight {
+			if Self::on_chain_storage_version() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+			if !SupplyCap::<T>::exists() {
+				SupplyCap::<T>::put(T::MaxTokenSupply::get());
+			}
+			StorageVersion::new(1).put::<Self>();
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+
+		/// v1 stored `()` for frozen accounts, v2 stores the reason they were frozen for
+		/// Accounts frozen before v2 keep their freeze with an empty reason
+		fn migrate_to_v2() -> Weight {
+			if Self::on_chain_storage_version() >= 2 {
+				return T::DbWeight::get().reads(1)
+			}
+			let mut frozen: Weight = 0;
+			FrozenAccounts::<T>::translate::<(), _>(|_, ()| {
+				frozen += 1;
+				Some(BoundedVec::default())
+			});
+			STORAGE_VERSION.put::<Self>();
+			T::DbWeight::get().reads_writes(frozen + 1, frozen + 1)
+		}
+
+		/// `amount * numerator / denominator`, rounded down
+		fn rescale_amount(
+			amount: T::Balance,
+			numerator: u32,
+			denominator: u32,
+		) -> Result<T::Balance, DispatchError> {
+			Self::balance_to_u128(amount)
+				.and_then(|amount| {
+					multiply_by_rational(amount, numerator.into(), denominator.into()).ok()
+				})
+				.and_then(Self::u128_to_balance)
+				.ok_or_else(|| ArithmeticError::Overflow.into())
+		}
+
+		/// `amount` passed through `rescale`, except for infinite allowances which stay infinite
+		fn rescale_allowance(
+			amount: T::Balance,
+			rescale: impl Fn(T::Balance) -> Result<T::Balance, DispatchError>,
+		) -> Result<T::Balance, DispatchError> {
+			if amount == T::Balance::max_value() {
+				return Ok(amount)
+			}
+			rescale(amount)
+		}
+
+		/// Mint checks and bookkeeping behind the `mint` extrinsic, once the origin is checked
+		/// Every check comes before the first write
+		fn do_mint(amount: T::Balance, benefactor: T::AccountId) -> DispatchResult {
+			Self::ensure_min_mint(amount)?;
+			// Ensure No MaxTokenSupply or Balance type overflow
+			// unless the runtime allows minting whatever headroom is left
+			let requested = amount;
+			let amount = if T::SaturatingArithmetic::get() {
+				let fits = amount.min(Self::type_headroom());
+				ensure!(fits == amount || !fits.is_zero(), Error::<T>::MintTypeOverflow);
+				fits
+			} else {
+				amount
+			};
+			let amount = match Self::does_adding_overflow_maxtokensupply(amount) {
+				Ok(()) => amount,
+				Err(_) if T::PartialMintAllowed::get() => {
+					let headroom = Self::supply_cap().saturating_sub(Self::total_issued());
+					ensure!(!headroom.is_zero(), Error::<T>::MintCausingTotalSupplyOverflow);
+					// Decayed amounts round on their own, so the headroom can still be a unit
+					// too much once it's added to the supply
+					Self::does_adding_overflow_maxtokensupply(headroom)?;
+					headroom
+				},
+				Err(_) => return Err(Error::<T>::MintCausingTotalSupplyOverflow.into()),
+			};
+			// The supply cap only guards the total, the benefactor's own balance can still
+			// overflow the Balance type on its own
+			let units = Self::to_units(amount).ok_or(Error::<T>::MintTypeOverflow)?;
+			if !T::SaturatingArithmetic::get() {
+				BalanceToAccount::<T>::get(&benefactor)
+					.checked_add(&units)
+					.ok_or(Error::<T>::MintTypeOverflow)?;
+			}
+			Self::ensure_can_receive(&benefactor, amount)?;
+			// Last check, since it also counts the mint towards this block's total
+			Self::include_in_block_mint_rate(amount)?;
+			if amount != requested {
+				Self::deposit_event(Event::PartialMint(requested, amount));
+			}
+
+			// Credits on top of whatever the benefactor already holds
+			Self::credit(&benefactor, units);
+			Self::note_received(&benefactor);
+			// Call to this helper updates `TotalIssued` storage item that tracks all minted counts
+			// in existence
+			Self::include_mint_amount(amount, units);
+			Self::deposit_event(Event::MintedNewSupply {
+				to: benefactor,
+				amount,
+				total_issued: Self::total_issued(),
+			});
+			if Self::total_issued() == Self::supply_cap() {
+				Self::deposit_event(Event::SupplyCapReached(Self::supply_cap()));
+			}
+			#[cfg(debug_assertions)]
+			Self::assert_issuance_consistent();
+			Ok(())
+		}
+
+		/// Checks and legs behind the `mint_batch` extrinsic, every check comes before the first
+		/// leg is applied
+		fn do_mint_batch(mints: Vec<(T::AccountId, T::Balance)>) -> DispatchResult {
+			ensure!(mints.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+			let mut legs = Vec::with_capacity(mints.len());
+			let (mut total, mut total_units) = (T::Balance::zero(), T::Balance::zero());
+			for (benefactor, amount) in mints {
+				Self::ensure_min_mint(amount)?;
+				let units = Self::to_units(amount).ok_or(Error::<T>::MintTypeOverflow)?;
+				total = total.checked_add(&amount).ok_or(Error::<T>::MintTypeOverflow)?;
+				total_units =
+					total_units.checked_add(&units).ok_or(Error::<T>::MintTypeOverflow)?;
+				legs.push((benefactor, amount, units));
+			}
+			// Each leg's units round on their own, so the cap is checked against their sum
+			let new_supply = TotalIssued::<T>::get()
+				.checked_add(&total_units)
+				.map(Self::from_units)
+				.ok_or(Error::<T>::MintTypeOverflow)?;
+			ensure!(new_supply <= Self::supply_cap(), Error::<T>::MintCausingTotalSupplyOverflow);
+			// The same benefactor may show up in several legs, so check caps per benefactor
+			let mut incoming = BTreeMap::<T::AccountId, T::Balance>::new();
+			for (benefactor, amount, _) in legs.iter() {
+				let received = incoming.entry(benefactor.clone()).or_insert_with(Zero::zero);
+				*received = received.saturating_add(*amount);
+			}
+			for (benefactor, received) in incoming.iter() {
+				Self::ensure_can_receive(benefactor, *received)?;
+			}
+			// Last check, since it also counts the batch towards this block's total
+			Self::include_in_block_mint_rate(total)?;
+			for (benefactor, amount, units) in legs {
+				Self::credit(&benefactor, units);
+				Self::note_received(&benefactor);
+				Self::include_mint_amount(amount, units);
+				Self::deposit_event(Event::MintedNewSupply {
+					to: benefactor,
+					amount,
+					total_issued: Self::total_issued(),
+				});
+			}
+			if Self::total_issued() == Self::supply_cap() {
+				Self::deposit_event(Event::SupplyCapReached(Self::supply_cap()));
+			}
+			#[cfg(debug_assertions)]
+			Self::assert_issuance_consistent();
+			Ok(())
+		}
+
+		/// Fails if `amount` is below a nonzero `MinMintAmount`, shared by every mint path
+		fn ensure_min_mint(amount: T::Balance) -> DispatchResult {
+			ensure!(amount >= T::MinMintAmount::get(), Error::<T>::BelowMinMint);
+			Ok(())
+		}
+
+		/// Checks and legs behind the `transfer_batch` extrinsic, every check comes before the
+		/// first leg is applied
+		fn do_transfer_batch(
+			sender: T::AccountId,
+			transfers: Vec<(<T::Lookup as StaticLookup>::Source, T::Balance)>,
+		) -> DispatchResult {
+			ensure!(transfers.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+			// Resolve every destination and sum up the total before touching storage
+			let mut legs = Vec::with_capacity(transfers.len());
+			let mut total = T::Balance::zero();
+			for (dest, amount) in transfers {
+				let dest = T::Lookup::lookup(dest)?;
+				Self::ensure_transfer_allowed(&sender, &dest, amount)?;
+				total = total.checked_add(&amount).ok_or(ArithmeticError::Overflow)?;
+				legs.push((dest, amount));
+			}
+			ensure!(Self::has_sufficient_funds(&sender, total), Error::<T>::InsufficientFunds);
+			// The same recipient may show up in several legs, so check self caps per recipient
+			// against what it gets once the fee of every leg is taken out
+			let mut incoming = BTreeMap::<T::AccountId, T::Balance>::new();
+			for (dest, amount) in legs.iter().filter(|(dest, _)| dest != &sender) {
+				let (_, received) = Self::split_fee(*amount)?;
+				let total = incoming.entry(dest.clone()).or_insert_with(Zero::zero);
+				*total = total.saturating_add(received);
+			}
+			for (dest, received) in incoming.iter() {
+				Self::ensure_can_receive(dest, *received)?;
+			}
+			for (dest, amount) in legs {
+				Self::transfer_unchecked(&sender, &dest, amount)?;
+			}
+			#[cfg(debug_assertions)]
+			Self::assert_issuance_consistent();
+			Ok(())
+		}
+
+		/// Move `amount` out of `owner`'s account into `to`, spending `spender`'s allowance
+		/// Shared by `spend_from` and `transfer_from`, emitting `TransferSuccess` and
+		/// `SpentAllowance`
+		fn do_spend_from(
+			spender: T::AccountId,
+			owner: T::AccountId,
+			to: T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			Self::ensure_transfer_allowed(&owner, &to, amount)?;
+			if let Some(expiry) = Self::allowance_expiry(&owner, &spender) {
+				ensure!(
+					frame_system::Pallet::<T>::block_number() <= expiry,
+					Error::<T>::AllowanceExpired
+				);
+			}
+			Self::refresh_allowance(&owner, &spender);
+			let allowance = Self::allowance(&owner, &spender);
+			let infinite = allowance == T::Balance::max_value();
+			let remaining = if infinite {
+				allowance
+			} else {
+				allowance.checked_sub(&amount).ok_or(Error::<T>::InsufficientAllowance)?
+			};
+			ensure!(Self::has_sufficient_funds(&owner, amount), Error::<T>::InsufficientFunds);
+			Self::transfer_unchecked(&owner, &to, amount)?;
+			if remaining.is_zero() {
+				// Reads as zero all the same, and a recurring allowance is refilled on its own
+				Allowances::<T>::remove(&owner, &spender);
+				if !RecurringAllowances::<T>::contains_key(&owner, &spender) {
+					AllowanceExpiries::<T>::remove(&owner, &spender);
+				}
+			} else if !infinite {
+				// Infinite allowances are left as is, saving a write on every spend
+				Allowances::<T>::insert(&owner, &spender, remaining);
+			}
+			Self::deposit_event(Event::SpentAllowance { owner, spender, spent: amount, remaining });
+			#[cfg(debug_assertions)]
+			Self::assert_issuance_consistent();
+			Ok(())
+		}
+
+		/// Plain transfer shared by the transfer extrinsics
+		fn do_transfer(
+			sender: T::AccountId,
+			to: T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			Self::ensure_transfer_allowed(&sender, &to, amount)?;
+			ensure!(Self::has_sufficient_funds(&sender, amount), Error::<T>::InsufficientFunds);
+			Self::transfer_unchecked(&sender, &to, amount)?;
+			#[cfg(debug_assertions)]
+			Self::assert_issuance_consistent();
+			Ok(())
+		}
+
+		/// Least a keep-alive transfer leaves behind, never zero since krypt reaps accounts
+		/// once their balance hits zero
+		fn keep_alive_minimum() -> T::Balance {
+			T::ExistentialDeposit::get().max(One::one())
+		}
+
+		/// Return every offer still open that expires at `now` to its sender
+		/// Returns how many ids were looked at, for weight accounting
+		fn expire_offers(now: T::BlockNumber) -> u32 {
+			let ids = OfferExpiries::<T>::take(now);
+			for id in ids.iter() {
+				if let Some(offer) = Offers::<T>::take(id) {
+					Self::refund_offer(&offer);
+					Self::deposit_event(Event::OfferExpired(*id));
+				}
+			}
+			ids.len() as u32
+		}
+
+		/// Give escrowed funds back to the sender of an offer
+		/// This is the sender's own money coming back, so self-imposed caps don't apply
+		fn refund_offer(offer: &TransferOffer<T::AccountId, T::Balance, T::BlockNumber>) {
+			let balance = BalanceToAccount::<T>::get(&offer.from);
+			Self::set_balance(&offer.from, balance.saturating_add(offer.amount));
+		}
+
+		/// Take `amount` out of `who`'s balance and out of `TotalIssued`
+		fn burn_from(who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			Self::ensure_can_burn(amount)?;
+
+			Self::ensure_can_send(who)?;
+			ensure!(Self::has_sufficient_funds(who, amount), Error::<T>::InsufficientFunds);
+			ensure!(Self::held_long_enough(who), Error::<T>::HeldTooBriefly);
+
+			let units = Self::to_units(amount).ok_or(ArithmeticError::Overflow)?;
+			let new_balance = BalanceToAccount::<T>::get(who).saturating_sub(units);
+			Self::set_balance(who, new_balance);
+			TotalIssued::<T>::mutate(|total| *total = total.saturating_sub(units));
+			#[cfg(debug_assertions)]
+			Self::assert_issuance_consistent();
+			Ok(())
+		}
+
+		/// Check that `who` may be credited `amount` without overflowing its balance, going
+		/// over its own cap or over the pallet-wide `MaxBalancePerAccount`
+		fn ensure_can_receive(who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			if !T::SaturatingArithmetic::get() {
+				Self::to_units(amount)
+					.and_then(|units| BalanceToAccount::<T>::get(who).checked_add(&units))
+					.ok_or(Error::<T>::BalanceOverflow)?;
+			}
+			Self::ensure_within_self_cap(who, amount)?;
+			if let Some(cap) = T::MaxBalancePerAccount::get() {
+				ensure!(
+					BalanceCapExempt::<T>::contains_key(who) ||
+						Self::stays_within(who, amount, cap),
+					Error::<T>::BalanceCapExceeded
+				);
+			}
+			Ok(())
+		}
+
+		/// Check that `who` can receive `amount` without going over its self-imposed cap,
+		/// or that there is a sink to take the excess
+		fn ensure_within_self_cap(who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			if let Some(cap) = Self::self_max_balance(who) {
+				ensure!(
+					Self::stays_within(who, amount, cap) || T::SelfCapOverflowSink::get().is_some(),
+					Error::<T>::SelfCapExceeded
+				);
+			}
+			Ok(())
+		}
+
+		/// Whether `who`'s balance is still worth at most `cap` once it's credited `amount`
+		/// Compared in decay-index units, since that's how the credit is applied
+		fn stays_within(who: &T::AccountId, amount: T::Balance, cap: T::Balance) -> bool {
+			match (Self::to_units(amount), Self::to_units(cap)) {
+				(Some(amount), Some(cap)) =>
+					BalanceToAccount::<T>::get(who).saturating_add(amount) <= cap,
+				// No balance can hold enough units to go over such a cap
+				(_, None) => true,
+				(None, Some(_)) => false,
+			}
+		}
+
+		/// Add `units`, in decay-index units, to `who`'s balance, sending anything above its
+		/// self-imposed cap to `SelfCapOverflowSink`. Callers check `ensure_can_receive`
+		/// beforehand
+		fn credit(who: &T::AccountId, units: T::Balance) {
+			let balance = BalanceToAccount::<T>::get(who);
+			let (kept, overflow) =
+				match (Self::self_max_balance(who), T::SelfCapOverflowSink::get()) {
+					(Some(cap), Some(sink)) if &sink != who => {
+						// Capped in decay-index units, so the balance ends up worth exactly `cap`
+						let cap =
+							Self::to_units(cap).unwrap_or_else(|| balance.saturating_add(units));
+						let kept = cap.saturating_sub(balance).min(units);
+						(kept, units - kept)
+					},
+					_ => (units, Zero::zero()),
+				};
+			Self::deposit_into(who, kept);
+			if !overflow.is_zero() {
+				if let Some(sink) = T::SelfCapOverflowSink::get() {
+					Self::deposit_into(&sink, overflow);
+					let overflow = Self::from_units(overflow);
+					Self::deposit_event(Event::SelfCapOverflowRouted(who.clone(), sink, overflow));
+				}
+			}
+		}
+
+		/// Add `units` to `who`'s balance, saturating at the top of the Balance type
+		/// Only `SaturatingArithmetic` lets a credit get that far, and whatever doesn't fit
+		/// is gone, so it leaves `TotalIssued` too
+		fn deposit_into(who: &T::AccountId, units: T::Balance) {
+			let balance = BalanceToAccount::<T>::get(who);
+			let new_balance = balance.saturating_add(units);
+			Self::set_balance(who, new_balance);
+			let lost = units - (new_balance - balance);
+			if !lost.is_zero() {
+				TotalIssued::<T>::mutate(|total| *total = total.saturating_sub(lost));
+			}
+		}
+
+		/// Most that can still be minted before `TotalIssued` overflows the Balance type
+		fn type_headroom() -> T::Balance {
+			Self::from_units(T::Balance::max_value() - TotalIssued::<T>::get())
+		}
+
+		/// Debug builds check that balances and funds escrowed in open offers add up to
+		/// `TotalIssued`, catching any code path that lets the two drift apart
+		/// All of them are stored in decay-index units, so this holds exactly whatever decay
+		/// did. It goes over every account, so it never runs in release builds
+		#[cfg(debug_assertions)]
+		fn assert_issuance_consistent() {
+			let held = BalanceToAccount::<T>::iter_values()
+				.chain(Offers::<T>::iter_values().map(|offer| offer.amount))
+				.fold(T::Balance::zero(), |acc, amount| acc.saturating_add(amount));
+			assert_eq!(
+				held,
+				TotalIssued::<T>::get(),
+				"Balances and open offers must add up to TotalIssued"
+			);
+		}
+
+		/// Sum of the stored balances of `accounts`, in decay-index units
+		#[cfg(debug_assertions)]
+		fn sum_of_balances(accounts: &[Option<T::AccountId>]) -> T::Balance {
+			accounts.iter().flatten().fold(Zero::zero(), |acc: T::Balance, who| {
+				acc.saturating_add(BalanceToAccount::<T>::get(who))
+			})
+		}
+
+		/// `amount` in decay-index units, `None` if that doesn't fit into a Balance
+		/// Rounded up to the fewest units worth at least `amount`, so `from_units` turns the
+		/// result back into exactly `amount`
+		fn to_units(amount: T::Balance) -> Option<T::Balance> {
+			let index = Self::decay_index();
+			if index == DECAY_INDEX_ONE {
+				return Some(amount)
+			}
+			let units = Self::balance_to_u128(amount)
+				.and_then(|amount| multiply_by_rational(amount, DECAY_INDEX_ONE, index).ok())
+				.and_then(Self::u128_to_balance)?;
+			if Self::from_units(units) < amount {
+				units.checked_add(&One::one())
+			} else {
+				Some(units)
+			}
+		}
+
+		/// What `units` in decay-index units are worth now, rounded down
+		fn from_units(units: T::Balance) -> T::Balance {
+			let index = Self::decay_index();
+			if index == DECAY_INDEX_ONE {
+				return units
+			}
+			// The index never grows past `DECAY_INDEX_ONE`, so the result always fits
+			Self::balance_to_u128(units)
+				.and_then(|units| multiply_by_rational(units, index, DECAY_INDEX_ONE).ok())
+				.and_then(Self::u128_to_balance)
+				.unwrap_or_else(Zero::zero)
+		}
+
+		/// Shrink `DecayIndex` by `rate`, which takes that much off every balance, escrowed
+		/// offer and `TotalIssued` at once since they are all stored in decay-index units
+		/// The shrunk index is rounded as `Rounding` says. It never reaches zero, so
+		/// `to_units` can always divide by it
+		fn decay(rate: Permill) {
+			let kept = Permill::one() - rate;
+			DecayIndex::<T>::mutate(|index| *index = Self::apply_rate(kept, *index).max(1));
+		}
+
+		/// `rate` of `amount`, rounded as `Rounding` says
+		fn apply_rate(rate: Permill, amount: u128) -> u128 {
+			let accuracy = u128::from(Permill::ACCURACY);
+			let parts = u128::from(rate.deconstruct());
+			// Split `amount` around the accuracy so the product can't overflow
+			let rest = amount % accuracy * parts;
+			let floor = amount / accuracy * parts + rest / accuracy;
+			let fraction = rest % accuracy;
+			let round_up = match T::Rounding::get() {
+				RoundingPolicy::Down => false,
+				RoundingPolicy::Up => fraction != 0,
+				RoundingPolicy::NearestEven =>
+					2 * fraction > accuracy || (2 * fraction == accuracy && floor % 2 == 1),
+			};
+			// A fraction left over means `floor` is below `amount`, so this can't overflow
+			floor + u128::from(round_up)
+		}
+
+		/// Single place where balances are written, in decay-index units
+		/// An account appears in `BalanceToAccount` only while it holds funds, and frame_system
+		/// gets a provider reference for as long as it does, so the system never reaps an
+		/// account that still holds krypt tokens
+		/// Units no longer worth a single base unit are dropped along with the account, and
+		/// taken out of `TotalIssued` as well
+		fn set_balance(who: &T::AccountId, new_balance: T::Balance) {
+			let existed = BalanceToAccount::<T>::contains_key(who);
+			let old_balance = Self::get_balance_of(who);
+			let new_balance = if !new_balance.is_zero() && Self::from_units(new_balance).is_zero() {
+				TotalIssued::<T>::mutate(|total| *total = total.saturating_sub(new_balance));
+				Zero::zero()
+			} else {
+				new_balance
+			};
+			if new_balance.is_zero() {
+				if existed {
+					BalanceToAccount::<T>::remove(who);
+					Self::on_killed_account(who);
+				}
+			} else {
+				BalanceToAccount::<T>::insert(who, new_balance);
+				if !existed {
+					Self::on_new_account(who);
+				}
+			}
+			let new_balance = Self::from_units(new_balance);
+			if old_balance != new_balance {
+				T::OnBalanceChanged::on_changed(who, old_balance, new_balance);
+			}
+		}
+
+		fn on_new_account(who: &T::AccountId) {
+			HolderCount::<T>::mutate(|count| *count = count.saturating_add(1));
+			// The reference may still be held from before, if giving it back failed
+			if !ProviderRefs::<T>::contains_key(who) {
+				frame_system::Pallet::<T>::inc_providers(who);
+				ProviderRefs::<T>::insert(who, ());
+			}
+		}
+
+		fn on_killed_account(who: &T::AccountId) {
+			HolderCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+			// Never give back a reference krypt didn't take, it could be another pallet's
+			// Can only fail if other pallets still hold consumer references on the account,
+			// in which case krypt keeps its reference until the account is funded again
+			if ProviderRefs::<T>::contains_key(who) &&
+				frame_system::Pallet::<T>::dec_providers(who).is_ok()
+			{
+				ProviderRefs::<T>::remove(who);
+			}
+		}
+
+		fn note_received(who: &T::AccountId) {
+			LastReceived::<T>::insert(who, frame_system::Pallet::<T>::block_number());
+		}
+
+		fn held_long_enough(who: &T::AccountId) -> bool {
+			let min_hold = T::MinHoldBeforeBurn::get();
+			if min_hold.is_zero() {
+				return true
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			now >= Self::last_received(who).saturating_add(min_hold)
+		}
 
+		fn has_sufficient_funds(s: &T::AccountId, amount: T::Balance) -> bool {
+			BalanceToAccount::<T>::contains_key(s) && Self::get_balance_of(s) >= amount
+		}
+
+		/// Split `amount` into the fee `TransferFeeRate` takes and what the recipient gets
+		/// Rounded as `Rounding` says, the two always add up to `amount`
+		fn split_fee(amount: T::Balance) -> Result<(T::Balance, T::Balance), DispatchError> {
+			let fee = Self::balance_to_u128(amount)
+				.map(|amount| Self::apply_rate(T::TransferFeeRate::get(), amount))
+				.and_then(Self::u128_to_balance)
+				// Only a Balance type wider than u128 gets here, it rounds down
+				.unwrap_or_else(|| T::TransferFeeRate::get().mul_floor(amount));
+			Self::ensure_can_burn(fee)?;
+			Ok((fee, amount - fee))
+		}
+
+		/// Fails with `BurnDisabled` if `amount` is to leave circulation in a build with
+		/// `derived-issuance`, which only holds as long as nothing ever does
+		fn ensure_can_burn(amount: T::Balance) -> DispatchResult {
+			ensure!(
+				amount.is_zero() || !cfg!(feature = "derived-issuance"),
+				Error::<T>::BurnDisabled
+			);
 			Ok(())
 		}
+
+		/// How many storage entries a call may go over for a `max_entries` witness
+		/// One more than the witness, so that a witness too low for what is stored uses it up
+		fn witness_budget(max_entries: u32) -> usize {
+			(max_entries as usize).saturating_add(1)
+		}
+
+		/// Collect at most `budget` items of `iter`, taking what was collected off `budget`
+		/// Chained over every map a witness covers, the witness was too low if nothing is left
+		fn bounded_take_with_witness<I: Iterator>(iter: I, budget: &mut usize) -> Vec<I::Item> {
+			let taken = iter.take(*budget).collect::<Vec<_>>();
+			*budget -= taken.len();
+			taken
+		}
+
+		/// Move `amount` from `sender` to `to`, less the transfer fee, returning what `to`
+		/// received. Moving funds to oneself is a no-op and isn't charged a fee
+		/// Visible to the crate so tests can drive it past the extrinsics' own balance checks
+		pub(crate) fn transfer_unchecked(
+			sender: &T::AccountId,
+			to: &T::AccountId,
+			amount: T::Balance,
+		) -> Result<T::Balance, DispatchError> {
+			// Callers already check `has_sufficient_funds`, but never trust that blindly
+			let units = Self::to_units(amount).ok_or(ArithmeticError::Overflow)?;
+			let new_sender_balance = BalanceToAccount::<T>::get(sender)
+				.checked_sub(&units)
+				.ok_or(Error::<T>::InsufficientFunds)?;
+			// Nothing to move, and no fee is charged for it
+			if sender == to {
+				return Ok(amount)
+			}
+			let (fee, received) = Self::split_fee(amount)?;
+			// Nothing is rolled back on error, so make sure the credit fits before debiting
+			Self::ensure_can_receive(to, received)?;
+			// Debug builds check that the transfer only moved funds around, catching any
+			// credit/debit asymmetry without costing anything in release builds
+			// The overflow sink is included since it may receive part of the transfer
+			#[cfg(debug_assertions)]
+			let parties = [Some(sender.clone()), Some(to.clone()), T::SelfCapOverflowSink::get()];
+			#[cfg(debug_assertions)]
+			let (sum_before, issued_before) = (Self::sum_of_balances(&parties), TotalIssued::<T>::get());
+			Self::set_balance(sender, new_sender_balance);
+			Self::settle_transfer(sender, to, units, fee, received);
+			// The burned fee and any worthless remainder leave `TotalIssued` along with the
+			// balances they came out of. Saturating credits can destroy funds on top of that,
+			// which `assert_issuance_consistent` covers
+			#[cfg(debug_assertions)]
+			assert!(
+				T::SaturatingArithmetic::get() ||
+					sum_before.saturating_sub(Self::sum_of_balances(&parties)) ==
+						issued_before.saturating_sub(TotalIssued::<T>::get()),
+				"A transfer must only move funds around, apart from what leaves TotalIssued"
+			);
+			Ok(received)
+		}
+
+		/// Deliver `units`, already taken out of `from`'s balance or escrow, to `to`
+		/// `to` is credited what `received` is worth and the rest, `fee` and any worthless
+		/// remainder, leaves `TotalIssued`. Callers check that the credit fits
+		/// Every transfer path ends up here, so this is where `TransferSuccess` is emitted
+		fn settle_transfer(
+			from: &T::AccountId,
+			to: &T::AccountId,
+			units: T::Balance,
+			fee: T::Balance,
+			received: T::Balance,
+		) {
+			// `received` is worth no more than `units`, so rounding up never takes more
+			let received_units = Self::to_units(received).map_or(units, |r| r.min(units));
+			let burned_units = units - received_units;
+			let is_new_account = !BalanceToAccount::<T>::contains_key(to);
+			// Credit on top of whatever the recipient already holds
+			Self::credit(to, received_units);
+			if !burned_units.is_zero() {
+				TotalIssued::<T>::mutate(|total| *total = total.saturating_sub(burned_units));
+			}
+			if !fee.is_zero() {
+				Self::deposit_event(Event::TransferFeeBurned { from: from.clone(), fee });
+			}
+			if is_new_account && !received.is_zero() && T::RecordAccountOrigin::get() {
+				AccountOrigin::<T>::insert(
+					to,
+					(from.clone(), frame_system::Pallet::<T>::block_number()),
+				);
+			}
+			Self::note_received(to);
+			let sent = fee.saturating_add(received);
+			LifetimeVolume::<T>::mutate(|volume| *volume = volume.saturating_add(sent));
+			Self::deposit_event(Event::TransferSuccess(from.clone(), to.clone(), received));
+		}
 	}
 }
@@ -0,0 +1,207 @@
+use crate as pallet_krypt;
+use crate::RoundingPolicy;
+use frame_support::{parameter_types, traits::EnsureOrigin, weights::constants::RocksDbWeight};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestSignature, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, LookupError, StaticLookup},
+	Permill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+// Extrinsic the offchain worker submits to the test transaction pool
+pub type Extrinsic = TestXt<Call, ()>;
+
+// Configure a mock runtime to test the pallet
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		Krypt: pallet_krypt,
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	// Nonzero, so rejected calls have reads to be charged for and writes to be refunded
+	type DbWeight = RocksDbWeight;
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IndexLookup;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+/// `INDEX_BASE + n` is the short index form of account `n`, only the first `INDEXED`
+/// accounts have one
+pub const INDEX_BASE: u64 = 1 << 32;
+pub const INDEXED: u64 = 100;
+
+/// Lookup of the mock, resolving account ids to themselves and indices the way an
+/// accounts-index pallet would
+pub struct IndexLookup;
+impl StaticLookup for IndexLookup {
+	type Source = u64;
+	type Target = u64;
+
+	fn lookup(source: u64) -> Result<u64, LookupError> {
+		match source.checked_sub(INDEX_BASE) {
+			None => Ok(source),
+			Some(index) if index < INDEXED => Ok(index),
+			Some(_) => Err(LookupError),
+		}
+	}
+
+	fn unlookup(target: u64) -> u64 {
+		target
+	}
+}
+
+/// Signed account the mock lets mint alongside Root
+pub const MINTER: u64 = 42;
+
+/// Mint origin of the mock, Root or the dedicated `MINTER` account
+pub struct EnsureRootOrMinter;
+impl EnsureOrigin<Origin> for EnsureRootOrMinter {
+	type Success = ();
+
+	fn try_origin(o: Origin) -> Result<Self::Success, Origin> {
+		o.into().and_then(|o| match o {
+			system::RawOrigin::Root => Ok(()),
+			system::RawOrigin::Signed(MINTER) => Ok(()),
+			o => Err(Origin::from(o)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> Origin {
+		Origin::root()
+	}
+}
+
+/// Supply cap of the mock
+pub const MAX_TOKEN_SUPPLY: u64 = 1_000_000;
+
+parameter_types! {
+	// Tests exercising the burn cooldown raise it with `MinHoldBeforeBurn::set`
+	pub static MinHoldBeforeBurn: u64 = 0;
+	pub static PartialMintAllowed: bool = false;
+	pub static SuppressRedundantSnapshots: bool = false;
+	// Zero leaves minting unlimited per block
+	pub static MintRatePerBlock: u64 = 0;
+	pub const MaxTokenSupply: u64 = MAX_TOKEN_SUPPLY;
+	pub const MaxBatchSize: u32 = 4;
+	pub static RecordAccountOrigin: bool = false;
+	pub const MaxMemoLength: u32 = 8;
+	pub const MaxFreezeReasonLength: u32 = 16;
+	// Credits over a self-imposed cap fail unless a test sets a sink
+	pub static SelfCapOverflowSink: Option<u64> = None;
+	pub const HistoryDepth: u32 = 4;
+	pub static Decimals: u8 = 0;
+	pub const OfferExpiry: u64 = 5;
+	pub const MaxOffersPerBlock: u32 = 2;
+	pub static ExistentialDeposit: u64 = 1;
+	pub const MaxMetadataLength: u32 = 4;
+	pub const StatsInterval: u64 = 10;
+	pub static TransferFeeRate: Permill = Permill::zero();
+	pub const MaxAccountsPage: u32 = 4;
+	pub static MaxBalancePerAccount: Option<u64> = None;
+	pub static DecayRatePerBlock: Permill = Permill::zero();
+	// Zero lets transfers of any size through
+	pub static MinTransferAmount: u64 = 0;
+	pub static SaturatingArithmetic: bool = false;
+	// Zero lets mints of any size through
+	pub static MinMintAmount: u64 = 0;
+	pub static Rounding: RoundingPolicy = RoundingPolicy::Down;
+	// Every (who, old, new) the handler below was called with, oldest first
+	pub static BalanceChanges: Vec<(u64, u64, u64)> = Vec::new();
+}
+
+/// Records every balance change krypt reports
+pub struct RecordBalanceChanges;
+impl pallet_krypt::OnBalanceChanged<u64, u64> for RecordBalanceChanges {
+	fn on_changed(who: &u64, old: u64, new: u64) {
+		let mut changes = BalanceChanges::get();
+		changes.push((*who, old, new));
+		BalanceChanges::set(changes);
+	}
+}
+
+impl pallet_krypt::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type MaxTokenSupply = MaxTokenSupply;
+	type MaxBatchSize = MaxBatchSize;
+	type MinHoldBeforeBurn = MinHoldBeforeBurn;
+	type PartialMintAllowed = PartialMintAllowed;
+	type SuppressRedundantSnapshots = SuppressRedundantSnapshots;
+	type MintRatePerBlock = MintRatePerBlock;
+	type RecordAccountOrigin = RecordAccountOrigin;
+	type MaxMemoLength = MaxMemoLength;
+	type AdminOrigin = system::EnsureRoot<u64>;
+	type MintOrigin = EnsureRootOrMinter;
+	type SelfCapOverflowSink = SelfCapOverflowSink;
+	type HistoryDepth = HistoryDepth;
+	type Decimals = Decimals;
+	type OfferExpiry = OfferExpiry;
+	type MaxOffersPerBlock = MaxOffersPerBlock;
+	type ExistentialDeposit = ExistentialDeposit;
+	type MaxMetadataLength = MaxMetadataLength;
+	type StatsInterval = StatsInterval;
+	type TransferFeeRate = TransferFeeRate;
+	type MaxAccountsPage = MaxAccountsPage;
+	type MaxBalancePerAccount = MaxBalancePerAccount;
+	type DecayRatePerBlock = DecayRatePerBlock;
+	type OnBalanceChanged = RecordBalanceChanges;
+	type MinTransferAmount = MinTransferAmount;
+	type MaxFreezeReasonLength = MaxFreezeReasonLength;
+	type SaturatingArithmetic = SaturatingArithmetic;
+	type MinMintAmount = MinMintAmount;
+	type Rounding = Rounding;
+	// Valid for a signer when it names that signer and the exact payload signed
+	type Signature = TestSignature;
+	type Signer = UintAuthorityId;
+}
+
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+	Call: From<C>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+// Build genesis storage according to the mock runtime
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext = sp_io::TestExternalities::new(storage);
+	// Events are only recorded from block 1 on
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
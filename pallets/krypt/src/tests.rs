@@ -0,0 +1,2853 @@
+use crate::{
+	mock::*, AccountStatus, Error, Event as KryptEvent, RoundingPolicy, SupplyStatsSnapshot,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_err, assert_noop, assert_ok,
+	dispatch::DispatchErrorWithPostInfo,
+	traits::{
+		GenesisBuild, Get, GetStorageVersion, OffchainWorker, OnFinalize, OnIdle, OnInitialize,
+		OnRuntimeUpgrade, StorageInfoTrait, StorageVersion,
+	},
+	weights::{GetDispatchInfo, Pays},
+};
+use sp_core::{
+	offchain::{testing::TestTransactionPoolExt, TransactionPoolExt},
+	H256,
+};
+use sp_runtime::{
+	testing::TestSignature,
+	traits::ValidateUnsigned,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	DispatchError, Perbill, Permill,
+};
+
+mod state_machine;
+
+#[test]
+fn genesis_can_set_a_supply_cap_below_the_constant() {
+	let storage =
+		crate::GenesisConfig::<Test> { balances: vec![(1, 300), (2, 200)], supply_cap: Some(600) }
+			.build_storage()
+			.unwrap();
+	sp_io::TestExternalities::new(storage).execute_with(|| {
+		assert_eq!(Krypt::supply_cap(), 600);
+		assert_eq!(Krypt::get_balance_of(&1), 300);
+		assert_eq!(Krypt::get_balance_of(&2), 200);
+		assert_eq!(Krypt::total_issued(), 500);
+		assert_eq!(Krypt::total_minted(), 500);
+		assert_eq!(Krypt::holder_count(), 2);
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 101, 3)),
+			Error::<Test>::MintCausingTotalSupplyOverflow
+		);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 3));
+	});
+}
+
+#[test]
+#[should_panic(expected = "Total sum in endowed accounts cannot exceed the supply cap")]
+fn genesis_rejects_balances_over_its_own_supply_cap() {
+	crate::GenesisConfig::<Test> { balances: vec![(1, 300), (2, 300)], supply_cap: Some(500) }
+		.build_storage()
+		.unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Total sum in endowed accounts cannot exceed the supply cap")]
+fn genesis_rejects_balances_over_max_token_supply_without_a_cap() {
+	crate::GenesisConfig::<Test> {
+		balances: vec![(1, MAX_TOKEN_SUPPLY), (2, 1)],
+		supply_cap: None,
+	}
+	.build_storage()
+	.unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Duplicate entries for accounts in genesis")]
+fn genesis_rejects_duplicate_accounts() {
+	crate::GenesisConfig::<Test> { balances: vec![(1, 10), (1, 20)], supply_cap: None }
+		.build_storage()
+		.unwrap();
+}
+
+#[test]
+fn mint_credits_the_benefactor() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_eq!(Krypt::get_balance_of(&1), 100);
+		assert_eq!(Krypt::total_issued(), 100);
+		System::assert_last_event(Event::Krypt(KryptEvent::MintedNewSupply {
+			to: 1,
+			amount: 100,
+			total_issued: 100,
+		}));
+	});
+}
+
+#[test]
+fn minted_new_supply_carries_the_benefactor_and_cumulative_issuance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 40, 2));
+		System::assert_last_event(Event::Krypt(KryptEvent::MintedNewSupply {
+			to: 2,
+			amount: 40,
+			total_issued: 140,
+		}));
+		assert_eq!(Krypt::get_balance_of(&2), 40);
+	});
+}
+
+#[test]
+fn mint_needs_root() {
+	new_test_ext().execute_with(|| {
+		assert!(Krypt::mint(Origin::signed(1), 100, 1).is_err());
+		assert_eq!(Krypt::total_issued(), 0);
+	});
+}
+
+#[test]
+fn mint_stops_at_max_token_supply() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), MAX_TOKEN_SUPPLY, 1));
+		System::assert_last_event(Event::Krypt(KryptEvent::SupplyCapReached(MAX_TOKEN_SUPPLY)));
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 1, 1)),
+			Error::<Test>::MintCausingTotalSupplyOverflow
+		);
+	});
+}
+
+#[test]
+fn rejected_mints_are_charged_less_than_successful_ones() {
+	new_test_ext().execute_with(|| {
+		let info = crate::Call::<Test>::mint { amount: 100, benefactor: 1 }.get_dispatch_info();
+		let rejected = Krypt::mint(Origin::root(), MAX_TOKEN_SUPPLY + 1, 1).unwrap_err();
+		assert_eq!(rejected.error, Error::<Test>::MintCausingTotalSupplyOverflow.into());
+		assert_eq!(rejected.post_info.pays_fee, Pays::Yes);
+		let accepted = Krypt::mint(Origin::root(), 100, 1).unwrap();
+		assert_eq!(accepted.calc_actual_weight(&info), info.weight);
+		assert!(rejected.post_info.calc_actual_weight(&info) < info.weight);
+	});
+}
+
+#[test]
+fn mints_below_the_minimum_amount_are_rejected() {
+	new_test_ext().execute_with(|| {
+		MinMintAmount::set(10);
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 9, 1)),
+			Error::<Test>::BelowMinMint
+		);
+		assert_ok!(Krypt::mint(Origin::root(), 10, 1));
+		assert_eq!(Krypt::get_balance_of(&1), 10);
+		// Issuing through the public helper goes through the same check
+		assert_eq!(Krypt::issue(9).err(), Some(Error::<Test>::BelowMinMint.into()));
+		drop(Krypt::issue(10).unwrap());
+		assert_eq!(Krypt::total_issued(), 10);
+	});
+}
+
+#[test]
+fn mint_batch_credits_every_leg() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint_batch(Origin::root(), vec![(1, 30), (2, 20), (1, 10)]));
+		assert_eq!(Krypt::get_balance_of(&1), 40);
+		assert_eq!(Krypt::get_balance_of(&2), 20);
+		assert_eq!(Krypt::total_issued(), 60);
+		System::assert_last_event(Event::Krypt(KryptEvent::MintedNewSupply {
+			to: 1,
+			amount: 10,
+			total_issued: 60,
+		}));
+		assert!(Krypt::mint_batch(Origin::signed(1), vec![(1, 10)]).is_err());
+	});
+}
+
+#[test]
+fn mint_batch_checks_every_leg_against_the_minimum_amount() {
+	new_test_ext().execute_with(|| {
+		MinMintAmount::set(10);
+		assert_noop!(
+			without_post_info(Krypt::mint_batch(Origin::root(), vec![(1, 10), (2, 9)])),
+			Error::<Test>::BelowMinMint
+		);
+		assert_ok!(Krypt::mint_batch(Origin::root(), vec![(1, 10), (2, 10)]));
+		assert_eq!(Krypt::total_issued(), 20);
+	});
+}
+
+#[test]
+fn mint_batch_fails_whole_when_it_goes_over_the_supply_cap() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			without_post_info(Krypt::mint_batch(
+				Origin::root(),
+				vec![(1, MAX_TOKEN_SUPPLY), (2, 1)]
+			)),
+			Error::<Test>::MintCausingTotalSupplyOverflow
+		);
+		assert_noop!(
+			without_post_info(Krypt::mint_batch(Origin::root(), vec![(1, 1); 5])),
+			Error::<Test>::BatchTooLarge
+		);
+	});
+}
+
+#[test]
+fn mint_whole_scales_by_decimals() {
+	new_test_ext().execute_with(|| {
+		Decimals::set(12);
+		assert_ok!(Krypt::set_supply_cap(Origin::root(), 5_000_000_000_000));
+		assert_ok!(Krypt::mint_whole(Origin::root(), 1, 3));
+		assert_eq!(Krypt::get_balance_of(&1), 3_000_000_000_000);
+		System::assert_last_event(Event::Krypt(KryptEvent::MintedNewSupply {
+			to: 1,
+			amount: 3_000_000_000_000,
+			total_issued: 3_000_000_000_000,
+		}));
+		// The supply cap is checked against the scaled amount
+		assert_noop!(
+			without_post_info(Krypt::mint_whole(Origin::root(), 1, 3)),
+			Error::<Test>::MintCausingTotalSupplyOverflow
+		);
+		assert!(Krypt::mint_whole(Origin::signed(1), 1, 1).is_err());
+	});
+}
+
+#[test]
+fn mint_whole_rejects_amounts_that_overflow_once_scaled() {
+	new_test_ext().execute_with(|| {
+		Decimals::set(12);
+		assert_ok!(Krypt::set_supply_cap(Origin::root(), u64::MAX));
+		let most_whole_tokens = u64::MAX / 1_000_000_000_000;
+		assert_noop!(
+			without_post_info(Krypt::mint_whole(Origin::root(), 1, most_whole_tokens + 1)),
+			Error::<Test>::MintTypeOverflow
+		);
+		assert_ok!(Krypt::mint_whole(Origin::root(), 1, most_whole_tokens));
+		assert_eq!(Krypt::get_balance_of(&1), most_whole_tokens * 1_000_000_000_000);
+	});
+}
+
+#[test]
+fn transfer_from_moves_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 40));
+		assert_eq!(Krypt::get_balance_of(&1), 60);
+		assert_eq!(Krypt::get_balance_of(&2), 40);
+		System::assert_has_event(Event::Krypt(KryptEvent::TransferSuccess(1, 2, 40)));
+	});
+}
+
+#[test]
+fn transfer_from_resolves_both_ends_through_the_lookup() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), INDEX_BASE + 1, INDEX_BASE + 2, 40));
+		assert_eq!(Krypt::get_balance_of(&1), 60);
+		assert_eq!(Krypt::get_balance_of(&2), 40);
+		// Unknown indices fail the lookup on either end
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), INDEX_BASE + INDEXED, 2, 10)),
+			DispatchError::CannotLookup
+		);
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, INDEX_BASE + INDEXED, 10)),
+			DispatchError::CannotLookup
+		);
+	});
+}
+
+#[test]
+fn transfer_from_another_account_spends_the_allowance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(2), INDEX_BASE + 1, 3, 10)),
+			Error::<Test>::InsufficientAllowance
+		);
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 30));
+		assert_ok!(Krypt::transfer_from(Origin::signed(2), INDEX_BASE + 1, 3, 10));
+		System::assert_has_event(Event::Krypt(KryptEvent::SpentAllowance {
+			owner: 1,
+			spender: 2,
+			spent: 10,
+			remaining: 20,
+		}));
+		assert_eq!(Krypt::get_balance_of(&3), 10);
+		assert_eq!(Krypt::allowance(&1, &2), 20);
+	});
+}
+
+#[test]
+fn transfer_from_emits_a_receipt_with_the_resulting_balances() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 5, 2));
+		let info =
+			crate::Call::<Test>::transfer_from { from: 1, to: 2, amount: 40 }.get_dispatch_info();
+		let post_info = Krypt::transfer_from(Origin::signed(1), 1, 2, 40).unwrap();
+		// Nothing to refund on success
+		assert_eq!(post_info.calc_actual_weight(&info), info.weight);
+		System::assert_last_event(Event::Krypt(KryptEvent::TransferReceipt {
+			from: 1,
+			to: 2,
+			amount: 40,
+			from_balance: 60,
+			to_balance: 45,
+		}));
+		System::assert_has_event(Event::Krypt(KryptEvent::TransferSuccess(1, 2, 40)));
+	});
+}
+
+#[test]
+fn transfer_from_rejects_insufficient_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 101)),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn frozen_accounts_receive_but_cannot_send() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::freeze(Origin::root(), 1, vec![]));
+		System::assert_last_event(Event::Krypt(KryptEvent::AccountFrozen(1, Default::default())));
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 10)),
+			Error::<Test>::AccountFrozen
+		);
+		assert_ok!(Krypt::mint(Origin::root(), 50, 1));
+		assert_eq!(Krypt::get_balance_of(&1), 150);
+
+		assert_ok!(Krypt::thaw(Origin::root(), 1));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 10));
+		assert_noop!(Krypt::thaw(Origin::root(), 1), Error::<Test>::NotFrozen);
+	});
+}
+
+#[test]
+fn freeze_reasons_are_recorded_until_thawed() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Krypt::freeze_reason(&1), None);
+		assert_ok!(Krypt::freeze(Origin::root(), 1, b"fraud".to_vec()));
+		System::assert_last_event(Event::Krypt(KryptEvent::AccountFrozen(
+			1,
+			b"fraud".to_vec().try_into().unwrap(),
+		)));
+		assert!(Krypt::is_frozen(&1));
+		assert_eq!(Krypt::freeze_reason(&1).unwrap().into_inner(), b"fraud".to_vec());
+
+		// Freezing again replaces the reason
+		assert_ok!(Krypt::freeze(Origin::root(), 1, b"court order".to_vec()));
+		assert_eq!(Krypt::freeze_reason(&1).unwrap().into_inner(), b"court order".to_vec());
+		assert_noop!(
+			Krypt::freeze(Origin::root(), 2, vec![b'x'; 17]),
+			Error::<Test>::FreezeReasonTooLong
+		);
+		assert!(!Krypt::is_frozen(&2));
+
+		assert_ok!(Krypt::thaw(Origin::root(), 1));
+		assert!(!Krypt::is_frozen(&1));
+		assert_eq!(Krypt::freeze_reason(&1), None);
+	});
+}
+
+#[test]
+fn blocked_destinations_reject_transfers_but_not_force_transfers() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 2, 10));
+		assert_ok!(Krypt::block_destination(Origin::root(), 2));
+		System::assert_last_event(Event::Krypt(KryptEvent::DestinationBlocked(2)));
+		assert!(Krypt::account_status(&2).blocked);
+
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 10)),
+			Error::<Test>::DestinationBlocked
+		);
+		assert_noop!(
+			without_post_info(Krypt::transfer_batch(Origin::signed(1), vec![(3, 10), (2, 10)])),
+			Error::<Test>::DestinationBlocked
+		);
+		assert_noop!(
+			Krypt::offer_transfer(Origin::signed(1), 2, 10),
+			Error::<Test>::DestinationBlocked
+		);
+		assert_noop!(
+			Krypt::accept_transfer(Origin::signed(2), 0),
+			Error::<Test>::DestinationBlocked
+		);
+
+		// The admin origin can still move funds there, e.g. for recovery
+		assert!(Krypt::force_transfer(Origin::signed(1), 1, 2, 10).is_err());
+		assert_ok!(Krypt::force_transfer(Origin::root(), 1, 2, 10));
+		System::assert_last_event(Event::Krypt(KryptEvent::ForceTransfer(1, 2, 10)));
+		assert_eq!(Krypt::get_balance_of(&1), 80);
+		assert_eq!(Krypt::get_balance_of(&2), 10);
+
+		assert_ok!(Krypt::unblock_destination(Origin::root(), 2));
+		System::assert_last_event(Event::Krypt(KryptEvent::DestinationUnblocked(2)));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 10));
+		assert_ok!(Krypt::accept_transfer(Origin::signed(2), 0));
+		assert_eq!(Krypt::get_balance_of(&2), 30);
+	});
+}
+
+#[test]
+fn pausing_stops_every_transfer() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::set_paused(Origin::root(), true));
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 10)),
+			Error::<Test>::TransfersPaused
+		);
+		assert_ok!(Krypt::set_paused(Origin::root(), false));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 10));
+	});
+}
+
+#[test]
+fn only_the_admin_origin_freezes_and_pauses() {
+	new_test_ext().execute_with(|| {
+		assert!(Krypt::freeze(Origin::signed(1), 2, vec![]).is_err());
+		assert!(Krypt::set_paused(Origin::signed(1), true).is_err());
+		assert!(!Krypt::is_frozen(&2));
+		assert!(!Krypt::is_paused());
+	});
+}
+
+#[test]
+fn account_status_starts_out_unrestricted() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert!(Krypt::account_status(&1).is_unrestricted());
+		assert!(Krypt::account_status(&2).is_unrestricted());
+	});
+}
+
+#[test]
+fn account_status_reports_every_restriction_at_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::freeze(Origin::root(), 1, vec![]));
+		assert_ok!(Krypt::set_paused(Origin::root(), true));
+		assert_eq!(
+			Krypt::account_status(&1),
+			AccountStatus {
+				frozen: true,
+				blocked: false,
+				paused: true,
+				cooldown: false,
+				capped: false
+			}
+		);
+		assert_eq!(
+			Krypt::account_status(&2),
+			AccountStatus {
+				frozen: false,
+				blocked: false,
+				paused: true,
+				cooldown: false,
+				capped: false
+			}
+		);
+
+		assert_ok!(Krypt::set_paused(Origin::root(), false));
+		assert_eq!(
+			Krypt::account_status(&1),
+			AccountStatus {
+				frozen: true,
+				blocked: false,
+				paused: false,
+				cooldown: false,
+				capped: false
+			}
+		);
+		assert_ok!(Krypt::thaw(Origin::root(), 1));
+		assert!(Krypt::account_status(&1).is_unrestricted());
+	});
+}
+
+#[test]
+fn transfer_batch_pays_every_leg() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_batch(Origin::signed(1), vec![(2, 10), (3, 20), (2, 5)]));
+		assert_eq!(Krypt::get_balance_of(&1), 65);
+		assert_eq!(Krypt::get_balance_of(&2), 15);
+		assert_eq!(Krypt::get_balance_of(&3), 20);
+		System::assert_has_event(Event::Krypt(KryptEvent::TransferSuccess(1, 2, 10)));
+		System::assert_has_event(Event::Krypt(KryptEvent::TransferSuccess(1, 3, 20)));
+		System::assert_last_event(Event::Krypt(KryptEvent::TransferSuccess(1, 2, 5)));
+	});
+}
+
+#[test]
+fn transfer_batch_applies_no_leg_when_the_total_is_not_covered() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		let events = System::events().len();
+		// Each leg alone is covered, the batch as a whole isn't
+		assert_noop!(
+			without_post_info(Krypt::transfer_batch(Origin::signed(1), vec![(2, 60), (3, 60)])),
+			Error::<Test>::InsufficientFunds
+		);
+		assert_eq!(Krypt::get_balance_of(&1), 100);
+		assert_eq!(Krypt::get_balance_of(&2), 0);
+		assert_eq!(Krypt::get_balance_of(&3), 0);
+		assert_eq!(System::events().len(), events);
+	});
+}
+
+#[test]
+fn transfer_batch_rejects_more_than_max_batch_size_legs() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		let legs = vec![(2, 1); MaxBatchSize::get() as usize + 1];
+		assert_noop!(
+			without_post_info(Krypt::transfer_batch(Origin::signed(1), legs)),
+			Error::<Test>::BatchTooLarge
+		);
+		assert_ok!(Krypt::transfer_batch(
+			Origin::signed(1),
+			vec![(2, 1); MaxBatchSize::get() as usize]
+		));
+	});
+}
+
+#[test]
+fn transfer_credits_on_top_of_the_recipient_balance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 30, 2));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 40));
+		assert_eq!(Krypt::get_balance_of(&2), 70);
+	});
+}
+
+#[test]
+fn account_status_cooldown_ends_after_min_hold() {
+	new_test_ext().execute_with(|| {
+		MinHoldBeforeBurn::set(10);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::freeze(Origin::root(), 1, vec![]));
+		assert_eq!(
+			Krypt::account_status(&1),
+			AccountStatus {
+				frozen: true,
+				blocked: false,
+				paused: false,
+				cooldown: true,
+				capped: false
+			}
+		);
+		System::set_block_number(11);
+		assert_eq!(
+			Krypt::account_status(&1),
+			AccountStatus {
+				frozen: true,
+				blocked: false,
+				paused: false,
+				cooldown: false,
+				capped: false
+			}
+		);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn burn_takes_funds_out_of_circulation() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::burn(Origin::signed(1), 40));
+		assert_eq!(Krypt::get_balance_of(&1), 60);
+		assert_eq!(Krypt::total_issued(), 60);
+		System::assert_last_event(Event::Krypt(KryptEvent::Burned(1, 40)));
+		assert_noop!(Krypt::burn(Origin::signed(1), 61), Error::<Test>::InsufficientFunds);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn burn_succeeds_once_funds_were_held_long_enough() {
+	new_test_ext().execute_with(|| {
+		MinHoldBeforeBurn::set(10);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		System::set_block_number(11);
+		assert_ok!(Krypt::burn(Origin::signed(1), 40));
+		assert_eq!(Krypt::get_balance_of(&1), 60);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn burn_fails_when_funds_were_held_too_briefly() {
+	new_test_ext().execute_with(|| {
+		MinHoldBeforeBurn::set(10);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		System::set_block_number(10);
+		assert_noop!(Krypt::burn(Origin::signed(1), 40), Error::<Test>::HeldTooBriefly);
+
+		// Receiving more funds restarts the cooldown
+		System::set_block_number(11);
+		assert_ok!(Krypt::mint(Origin::root(), 200, 2));
+		assert_ok!(Krypt::transfer_from(Origin::signed(2), 2, 1, 10));
+		assert_noop!(Krypt::burn(Origin::signed(1), 40), Error::<Test>::HeldTooBriefly);
+		System::set_block_number(21);
+		assert_ok!(Krypt::burn(Origin::signed(1), 40));
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn zero_min_hold_before_burn_allows_burning_right_away() {
+	new_test_ext().execute_with(|| {
+		MinHoldBeforeBurn::set(0);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::burn(Origin::signed(1), 100));
+		assert_eq!(Krypt::total_issued(), 0);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn lifetime_volume_counts_transfers_only() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_eq!(Krypt::lifetime_volume(), 0);
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 30));
+		assert_ok!(Krypt::transfer_batch(Origin::signed(1), vec![(2, 5), (3, 15)]));
+		assert_ok!(Krypt::transfer_from(Origin::signed(2), 2, 1, 10));
+		assert_ok!(Krypt::burn(Origin::signed(1), 10));
+		assert_eq!(Krypt::lifetime_volume(), 60);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn lifetime_volume_counts_what_was_sent_before_the_fee() {
+	new_test_ext().execute_with(|| {
+		TransferFeeRate::set(Permill::from_percent(10));
+		assert_ok!(Krypt::mint(Origin::root(), 1_000, 1));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 100));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 3, 100));
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 100));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 3, 100));
+		assert_ok!(Krypt::accept_transfer(Origin::signed(3), 0));
+		// Moves nothing, so it isn't counted
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 1, 100));
+		assert_eq!(Krypt::lifetime_volume(), 300);
+	});
+}
+
+#[test]
+fn mint_over_the_cap_fails_without_partial_mints() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), MAX_TOKEN_SUPPLY - 100, 1));
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 150, 2)),
+			Error::<Test>::MintCausingTotalSupplyOverflow
+		);
+	});
+}
+
+#[test]
+fn mint_over_the_cap_mints_the_headroom_with_partial_mints() {
+	new_test_ext().execute_with(|| {
+		PartialMintAllowed::set(true);
+		assert_ok!(Krypt::mint(Origin::root(), MAX_TOKEN_SUPPLY - 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 150, 2));
+		assert_eq!(Krypt::get_balance_of(&2), 100);
+		assert_eq!(Krypt::total_issued(), MAX_TOKEN_SUPPLY);
+		System::assert_has_event(Event::Krypt(KryptEvent::PartialMint(150, 100)));
+		// Nothing is left to mint once the cap is reached
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 1, 2)),
+			Error::<Test>::MintCausingTotalSupplyOverflow
+		);
+	});
+}
+
+#[test]
+fn median_balance_of_no_accounts_is_zero() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Krypt::median_balance(), 0);
+	});
+}
+
+#[test]
+fn median_balance_of_an_odd_number_of_accounts_is_the_middle_one() {
+	new_test_ext().execute_with(|| {
+		for (who, amount) in [(1, 500), (2, 10), (3, 70)] {
+			assert_ok!(Krypt::mint(Origin::root(), amount, who));
+		}
+		assert_eq!(Krypt::median_balance(), 70);
+	});
+}
+
+#[test]
+fn median_balance_of_an_even_number_of_accounts_is_the_mean_of_the_middle_two() {
+	new_test_ext().execute_with(|| {
+		for (who, amount) in [(1, 500), (2, 10), (3, 70), (4, 75)] {
+			assert_ok!(Krypt::mint(Origin::root(), amount, who));
+		}
+		// (70 + 75) / 2, rounded down
+		assert_eq!(Krypt::median_balance(), 72);
+	});
+}
+
+#[test]
+fn spend_from_uses_up_the_allowance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 50));
+		System::assert_last_event(Event::Krypt(KryptEvent::Approval(1, 2, 50)));
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 30));
+		assert_eq!(Krypt::get_balance_of(&1), 70);
+		assert_eq!(Krypt::get_balance_of(&3), 30);
+		assert_eq!(Krypt::allowance(&1, &2), 20);
+		assert_noop!(
+			Krypt::spend_from(Origin::signed(2), 1, 3, 21),
+			Error::<Test>::InsufficientAllowance
+		);
+		// Nobody else can spend it
+		assert_noop!(
+			Krypt::spend_from(Origin::signed(3), 1, 3, 1),
+			Error::<Test>::InsufficientAllowance
+		);
+	});
+}
+
+#[test]
+fn spend_from_needs_the_owner_to_hold_the_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 10, 1));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 50));
+		assert_noop!(
+			Krypt::spend_from(Origin::signed(2), 1, 3, 20),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn recurring_allowance_refreshes_every_period() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 1_000, 1));
+		assert_ok!(Krypt::approve_recurring(Origin::signed(1), 2, 50, 10));
+		System::assert_last_event(Event::Krypt(KryptEvent::RecurringApproval(1, 2, 50, 10)));
+
+		// Never more than `amount` within one period
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 2, 30));
+		System::set_block_number(10);
+		assert_noop!(
+			Krypt::spend_from(Origin::signed(2), 1, 2, 21),
+			Error::<Test>::InsufficientAllowance
+		);
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 2, 20));
+
+		// Unspent allowance doesn't carry over into the next period
+		System::set_block_number(11);
+		assert_err!(
+			Krypt::spend_from(Origin::signed(2), 1, 2, 51),
+			Error::<Test>::InsufficientAllowance
+		);
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 2, 50));
+		assert_eq!(Krypt::allowance(&1, &2), 0);
+
+		// Skipped periods don't stack up, and the period stays aligned to its start
+		System::set_block_number(35);
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 2, 50));
+		assert_eq!(Krypt::recurring_allowance(&1, &2).unwrap().last_reset, 31);
+		assert_eq!(Krypt::get_balance_of(&2), 150);
+	});
+}
+
+#[test]
+fn approve_replaces_a_recurring_allowance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 1_000, 1));
+		assert_ok!(Krypt::approve_recurring(Origin::signed(1), 2, 50, 10));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 5));
+		assert_eq!(Krypt::recurring_allowance(&1, &2), None);
+		System::set_block_number(20);
+		assert_eq!(Krypt::allowance(&1, &2), 5);
+	});
+}
+
+#[test]
+fn approving_zero_removes_the_allowance_entry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::approve_with_expiry(Origin::signed(1), 2, 50, 10));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 0));
+		System::assert_last_event(Event::Krypt(KryptEvent::Approval(1, 2, 0)));
+		assert!(!crate::Allowances::<Test>::contains_key(1, 2));
+		assert_eq!(Krypt::allowance_expiry(1, 2), None);
+
+		assert_ok!(Krypt::permit(Origin::signed(3), 1, 3, 20, 10, sign_permit(1, 3, 20, 0, 10)));
+		assert_ok!(Krypt::permit(Origin::signed(3), 1, 3, 0, 10, sign_permit(1, 3, 0, 1, 10)));
+		assert!(!crate::Allowances::<Test>::contains_key(1, 3));
+	});
+}
+
+#[test]
+fn no_path_stores_a_zero_allowance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::approve_with_expiry(Origin::signed(1), 2, 0, 10));
+		assert!(!crate::Allowances::<Test>::contains_key(1, 2));
+		assert_eq!(Krypt::allowance_expiry(1, 2), None);
+
+		// A third of 2 rounds down to nothing
+		assert_ok!(Krypt::approve(Origin::signed(1), 3, 2));
+		assert_ok!(Krypt::rescale_allowances(Origin::root(), 1, 3, 1));
+		assert!(!crate::Allowances::<Test>::contains_key(1, 3));
+		assert_eq!(crate::Allowances::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn reapproving_the_same_allowance_is_a_cheap_no_op() {
+	new_test_ext().execute_with(|| {
+		let info = crate::Call::<Test>::approve { spender: 2, amount: 50 }.get_dispatch_info();
+		let first = Krypt::approve(Origin::signed(1), 2, 50).unwrap();
+		assert_eq!(first.calc_actual_weight(&info), info.weight);
+		System::assert_last_event(Event::Krypt(KryptEvent::Approval(1, 2, 50)));
+		let events = System::events().len();
+
+		let again = Krypt::approve(Origin::signed(1), 2, 50).unwrap();
+		assert!(again.calc_actual_weight(&info) < info.weight);
+		assert_eq!(System::events().len(), events);
+		assert_eq!(Krypt::allowance(&1, &2), 50);
+
+		// A recurring allowance of the same amount still becomes a plain one
+		assert_ok!(Krypt::approve_recurring(Origin::signed(1), 2, 50, 10));
+		let replaced = Krypt::approve(Origin::signed(1), 2, 50).unwrap();
+		assert_eq!(replaced.calc_actual_weight(&info), info.weight);
+		System::assert_last_event(Event::Krypt(KryptEvent::Approval(1, 2, 50)));
+		assert_eq!(Krypt::recurring_allowance(&1, &2), None);
+	});
+}
+
+#[test]
+fn recurring_allowance_needs_a_period() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Krypt::approve_recurring(Origin::signed(1), 2, 50, 0),
+			Error::<Test>::ZeroPeriod
+		);
+	});
+}
+
+/// `owner`'s signature over a permit for `spender` on this chain, as `permit` expects it
+fn sign_permit(owner: u64, spender: u64, amount: u64, nonce: u64, deadline: u64) -> TestSignature {
+	sign_permit_for(System::block_hash(0), owner, spender, amount, nonce, deadline)
+}
+
+/// `owner`'s signature over a permit for `spender` on the chain with `genesis_hash`
+fn sign_permit_for(
+	genesis_hash: H256,
+	owner: u64,
+	spender: u64,
+	amount: u64,
+	nonce: u64,
+	deadline: u64,
+) -> TestSignature {
+	let payload = (b"krypt/permit", genesis_hash, owner, spender, amount, nonce, deadline);
+	TestSignature(owner, payload.encode())
+}
+
+#[test]
+fn permit_sets_an_allowance_the_owner_signed() {
+	new_test_ext().execute_with(|| {
+		// Account 3 relays the permit, 1 never submits anything itself
+		let signature = sign_permit(1, 2, 50, 0, 10);
+		assert_ok!(Krypt::permit(Origin::signed(3), 1, 2, 50, 10, signature));
+		System::assert_last_event(Event::Krypt(KryptEvent::Approval(1, 2, 50)));
+		assert_eq!(Krypt::allowance(&1, &2), 50);
+		assert_eq!(Krypt::permit_nonce(&1), 1);
+	});
+}
+
+#[test]
+fn permits_can_only_be_used_once() {
+	new_test_ext().execute_with(|| {
+		let signature = sign_permit(1, 2, 50, 0, 10);
+		assert_ok!(Krypt::permit(Origin::signed(3), 1, 2, 50, 10, signature.clone()));
+		// The nonce moved on, so the same signature no longer matches
+		assert_noop!(
+			Krypt::permit(Origin::signed(3), 1, 2, 50, 10, signature),
+			Error::<Test>::InvalidPermitSignature
+		);
+		assert_ok!(Krypt::permit(Origin::signed(3), 1, 2, 20, 10, sign_permit(1, 2, 20, 1, 10)));
+		assert_eq!(Krypt::allowance(&1, &2), 20);
+	});
+}
+
+#[test]
+fn permits_signed_for_another_chain_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let elsewhere = H256::repeat_byte(7);
+		assert_ne!(System::block_hash(0), elsewhere);
+		let signature = sign_permit_for(elsewhere, 1, 2, 50, 0, 10);
+		assert_noop!(
+			Krypt::permit(Origin::signed(3), 1, 2, 50, 10, signature),
+			Error::<Test>::InvalidPermitSignature
+		);
+		assert_eq!(Krypt::permit_nonce(&1), 0);
+	});
+}
+
+#[test]
+fn permits_past_their_deadline_are_rejected() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(11);
+		assert_noop!(
+			Krypt::permit(Origin::signed(3), 1, 2, 50, 10, sign_permit(1, 2, 50, 0, 10)),
+			Error::<Test>::PermitExpired
+		);
+		assert_eq!(Krypt::permit_nonce(&1), 0);
+	});
+}
+
+#[test]
+fn permits_signed_by_someone_else_are_rejected() {
+	new_test_ext().execute_with(|| {
+		// The owner's permit, but signed by the spender
+		let forged = TestSignature(2, sign_permit(1, 2, 50, 0, 10).1);
+		assert_noop!(
+			Krypt::permit(Origin::signed(2), 1, 2, 50, 10, forged),
+			Error::<Test>::InvalidPermitSignature
+		);
+		// Signed by the owner, but for a different amount
+		assert_noop!(
+			Krypt::permit(Origin::signed(3), 1, 2, 60, 10, sign_permit(1, 2, 50, 0, 10)),
+			Error::<Test>::InvalidPermitSignature
+		);
+		assert_eq!(Krypt::allowance(&1, &2), 0);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn donate_to_burn_counts_community_burns() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::donate_to_burn(Origin::signed(1), 30));
+		assert_eq!(Krypt::get_balance_of(&1), 70);
+		assert_eq!(Krypt::total_issued(), 70);
+		assert_eq!(Krypt::community_burned(), 30);
+		System::assert_last_event(Event::Krypt(KryptEvent::CommunityBurn(1, 30)));
+
+		// Plain burns aren't community burns
+		assert_ok!(Krypt::burn(Origin::signed(1), 20));
+		assert_eq!(Krypt::community_burned(), 30);
+		assert_noop!(
+			Krypt::donate_to_burn(Origin::signed(1), 51),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn transfer_from_preserves_the_balance_sum() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 50, 2));
+		// Debug builds assert the sum of both balances is unchanged inside each of these
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 3, 10));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 40));
+		assert_ok!(Krypt::transfer_from(Origin::signed(2), 2, 2, 90));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 50));
+		assert_eq!(Krypt::get_balance_of(&1) + Krypt::get_balance_of(&2), 140);
+	});
+}
+
+#[test]
+fn set_test_state_reads_back_through_the_getters() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 500, 9));
+		assert_ok!(Krypt::approve(Origin::signed(9), 1, 5));
+		Krypt::set_test_state(vec![(1, 100), (2, 50)], vec![(1, 2, 30)], 150);
+		assert_eq!(Krypt::get_balance_of(&1), 100);
+		assert_eq!(Krypt::get_balance_of(&2), 50);
+		assert_eq!(Krypt::allowance(&1, &2), 30);
+		assert_eq!(Krypt::total_issued(), 150);
+		// Whatever was there before is gone
+		assert_eq!(Krypt::get_balance_of(&9), 0);
+		assert_eq!(Krypt::allowance(&9, &1), 0);
+
+		// The loaded state works like any other
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 30));
+		assert_eq!(Krypt::get_balance_of(&3), 30);
+	});
+}
+
+#[test]
+#[should_panic(expected = "total_issued must equal the sum of all balances")]
+fn set_test_state_rejects_an_inconsistent_total() {
+	new_test_ext().execute_with(|| {
+		Krypt::set_test_state(vec![(1, 100), (2, 50)], vec![], 100);
+	});
+}
+
+#[test]
+fn first_credit_adds_a_provider_reference() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(System::providers(&1), 0);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_eq!(System::providers(&1), 1);
+		// Topping up an existing holder doesn't add another
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_eq!(System::providers(&1), 1);
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 50));
+		assert_eq!(System::providers(&2), 1);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn emptied_accounts_drop_their_provider_reference() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 2));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 3, 100));
+		assert_eq!(System::providers(&1), 0);
+		assert_eq!(Krypt::get_balance_of(&1), 0);
+
+		assert_ok!(Krypt::burn(Origin::signed(2), 100));
+		assert_eq!(System::providers(&2), 0);
+		assert_eq!(System::providers(&3), 1);
+	});
+}
+
+#[test]
+fn provider_references_krypt_never_took_are_left_alone() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		// As if 1 was funded before krypt took provider references, and the one it has
+		// belongs to another pallet
+		crate::ProviderRefs::<Test>::remove(1);
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 100));
+		assert_eq!(Krypt::get_balance_of(&1), 0);
+		assert_eq!(System::providers(&1), 1);
+
+		// Funding it again takes a reference of krypt's own, which it does give back
+		assert_ok!(Krypt::transfer_from(Origin::signed(2), 2, 1, 100));
+		assert_eq!(System::providers(&1), 2);
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 100));
+		assert_eq!(System::providers(&1), 1);
+	});
+}
+
+#[test]
+fn set_test_state_keeps_provider_references_consistent() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		Krypt::set_test_state(vec![(2, 100)], vec![], 100);
+		assert_eq!(System::providers(&1), 0);
+		assert_eq!(System::providers(&2), 1);
+	});
+}
+
+#[test]
+fn set_test_state_drops_recurring_allowances_and_offers() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::approve_recurring(Origin::signed(1), 2, 50, 10));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 3, 10));
+
+		Krypt::set_test_state(vec![(2, 40)], vec![], 40);
+		assert_eq!(Krypt::recurring_allowance(1, 2), None);
+		assert_eq!(Krypt::offer(0), None);
+		assert!(crate::OfferExpiries::<Test>::iter().next().is_none());
+		assert_eq!(Krypt::total_minted(), 40);
+		assert_eq!(Krypt::total_issued(), 40);
+	});
+}
+
+fn snapshot_events() -> usize {
+	System::events()
+		.into_iter()
+		.filter(|record| matches!(record.event, Event::Krypt(KryptEvent::TotalIssued(_))))
+		.count()
+}
+
+#[test]
+#[allow(deprecated)]
+fn identical_snapshots_are_emitted_once_when_suppressed() {
+	new_test_ext().execute_with(|| {
+		SuppressRedundantSnapshots::set(true);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::total_issuance(Origin::signed(1)));
+		assert_ok!(Krypt::total_issuance(Origin::signed(1)));
+		assert_eq!(snapshot_events(), 1);
+		assert_eq!(Krypt::last_reported_issuance(), Some(100));
+
+		// A change is reported again
+		assert_ok!(Krypt::mint(Origin::root(), 50, 1));
+		assert_ok!(Krypt::total_issuance(Origin::signed(1)));
+		assert_eq!(snapshot_events(), 2);
+		System::assert_last_event(Event::Krypt(KryptEvent::TotalIssued(150)));
+	});
+}
+
+#[test]
+#[allow(deprecated)]
+fn every_snapshot_is_emitted_without_suppression() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::total_issuance(Origin::signed(1)));
+		assert_ok!(Krypt::total_issuance(Origin::signed(1)));
+		assert_eq!(snapshot_events(), 2);
+	});
+}
+
+#[test]
+fn mint_rate_resets_every_block() {
+	new_test_ext().execute_with(|| {
+		MintRatePerBlock::set(100);
+		assert_ok!(Krypt::mint(Origin::root(), 60, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 40, 2));
+		assert_eq!(Krypt::minted_this_block(), 100);
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 1, 1)),
+			Error::<Test>::MintRateExceeded
+		);
+
+		System::set_block_number(2);
+		assert_eq!(
+			Krypt::on_initialize(2),
+			<Test as frame_system::Config>::DbWeight::get().reads_writes(3, 3)
+		);
+		assert_eq!(Krypt::minted_this_block(), 0);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_eq!(Krypt::total_issued(), 200);
+	});
+}
+
+#[test]
+fn zero_mint_rate_disables_the_limit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), MAX_TOKEN_SUPPLY, 1));
+		assert_eq!(Krypt::minted_this_block(), MAX_TOKEN_SUPPLY);
+	});
+}
+
+#[test]
+fn partial_mint_counts_only_what_was_minted() {
+	new_test_ext().execute_with(|| {
+		PartialMintAllowed::set(true);
+		MintRatePerBlock::set(MAX_TOKEN_SUPPLY);
+		assert_ok!(Krypt::mint(Origin::root(), MAX_TOKEN_SUPPLY - 10, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_eq!(Krypt::minted_this_block(), MAX_TOKEN_SUPPLY);
+		System::assert_has_event(Event::Krypt(KryptEvent::PartialMint(100, 10)));
+	});
+}
+
+#[test]
+fn transfer_to_a_fresh_account_records_its_origin() {
+	new_test_ext().execute_with(|| {
+		RecordAccountOrigin::set(true);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		System::set_block_number(5);
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 10));
+		assert_eq!(Krypt::account_origin(&2), Some((1, 5)));
+		// Minted accounts weren't created by a transfer
+		assert_eq!(Krypt::account_origin(&1), None);
+	});
+}
+
+#[test]
+fn transfer_to_an_existing_account_keeps_its_origin() {
+	new_test_ext().execute_with(|| {
+		RecordAccountOrigin::set(true);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 3));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 10));
+		System::set_block_number(5);
+		assert_ok!(Krypt::transfer_from(Origin::signed(3), 3, 2, 10));
+		assert_eq!(Krypt::account_origin(&2), Some((1, 1)));
+	});
+}
+
+#[test]
+fn account_origin_is_not_recorded_when_disabled() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 10));
+		assert_eq!(Krypt::account_origin(&2), None);
+	});
+}
+
+#[test]
+fn transfer_with_memo_emits_the_exact_memo() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		let memo = b"dep-4711".to_vec();
+		assert_ok!(Krypt::transfer_with_memo(Origin::signed(1), 2, 30, memo.clone()));
+		assert_eq!(Krypt::get_balance_of(&2), 30);
+		System::assert_has_event(Event::Krypt(KryptEvent::TransferSuccess(1, 2, 30)));
+		System::assert_last_event(Event::Krypt(KryptEvent::TransferWithMemo {
+			from: 1,
+			to: 2,
+			amount: 30,
+			memo: memo.try_into().unwrap(),
+		}));
+	});
+}
+
+#[test]
+fn transfer_with_memo_is_charged_per_memo_byte() {
+	let weight = |memo: Vec<u8>| {
+		crate::Call::<Test>::transfer_with_memo { to: 2, amount: 30, memo }
+			.get_dispatch_info()
+			.weight
+	};
+	let plain = crate::Call::<Test>::transfer_from { from: 1, to: 2, amount: 30 }
+		.get_dispatch_info()
+		.weight;
+	assert_eq!(weight(vec![]), plain);
+	assert_eq!(weight(vec![0; 8]), plain + 800);
+}
+
+#[test]
+fn transfer_with_memo_rejects_overlong_memos() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(
+			Krypt::transfer_with_memo(Origin::signed(1), 2, 30, vec![0; 9]),
+			Error::<Test>::MemoTooLong
+		);
+	});
+}
+
+#[test]
+fn transfer_with_memo_honors_freezes() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::freeze(Origin::root(), 1, vec![]));
+		assert_noop!(
+			Krypt::transfer_with_memo(Origin::signed(1), 2, 30, vec![]),
+			Error::<Test>::AccountFrozen
+		);
+	});
+}
+
+#[test]
+fn transfer_with_memo_runs_the_checks_of_a_plain_transfer() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::block_destination(Origin::root(), 2));
+		assert_noop!(
+			Krypt::transfer_with_memo(Origin::signed(1), 2, 30, vec![]),
+			Error::<Test>::DestinationBlocked
+		);
+		assert_ok!(Krypt::set_paused(Origin::root(), true));
+		assert_noop!(
+			Krypt::transfer_with_memo(Origin::signed(1), 3, 30, vec![]),
+			Error::<Test>::TransfersPaused
+		);
+		assert_ok!(Krypt::set_paused(Origin::root(), false));
+		assert_noop!(
+			Krypt::transfer_with_memo(Origin::signed(1), 3, 101, vec![]),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn derived_issuance_matches_every_mint() {
+	new_test_ext().execute_with(|| {
+		for (amount, who) in [(100, 1), (250, 2), (1, 1), (649, 3)] {
+			assert_ok!(Krypt::mint(Origin::root(), amount, who));
+			assert_eq!(Krypt::total_issued(), Krypt::total_minted());
+		}
+		assert_eq!(Krypt::total_minted(), 1_000);
+		// Transfers don't touch either counter
+		assert_ok!(Krypt::transfer_from(Origin::signed(2), 2, 3, 50));
+		assert_eq!(Krypt::total_issued(), 1_000);
+		assert_eq!(Krypt::total_minted(), 1_000);
+	});
+}
+
+#[test]
+#[cfg(feature = "derived-issuance")]
+fn burns_are_disabled_with_derived_issuance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(Krypt::burn(Origin::signed(1), 10), Error::<Test>::BurnDisabled);
+		assert_noop!(Krypt::donate_to_burn(Origin::signed(1), 10), Error::<Test>::BurnDisabled);
+		assert_noop!(Krypt::wipe_account(Origin::root(), 1, 0), Error::<Test>::BurnDisabled);
+		// An empty account has nothing to burn
+		assert_ok!(Krypt::wipe_account(Origin::root(), 2, 0));
+	});
+}
+
+#[test]
+fn transfer_to_a_new_account_adds_a_holder() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_eq!(Krypt::holder_count(), 1);
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 30));
+		assert_eq!(Krypt::holder_count(), 2);
+		// Paying an existing holder again doesn't count it twice
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 30));
+		assert_eq!(Krypt::holder_count(), 2);
+		// Moving everything out reaps the sender
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 40));
+		assert_eq!(Krypt::holder_count(), 1);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn burning_everything_removes_a_holder() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 2));
+		assert_ok!(Krypt::burn(Origin::signed(1), 50));
+		assert_eq!(Krypt::holder_count(), 2);
+		assert_ok!(Krypt::burn(Origin::signed(1), 50));
+		assert_eq!(Krypt::holder_count(), 1);
+	});
+}
+
+#[test]
+fn supply_cap_can_be_raised() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Krypt::supply_cap(), MAX_TOKEN_SUPPLY);
+		assert_ok!(Krypt::mint(Origin::root(), MAX_TOKEN_SUPPLY, 1));
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 1, 1)),
+			Error::<Test>::MintCausingTotalSupplyOverflow
+		);
+		assert_ok!(Krypt::set_supply_cap(Origin::root(), MAX_TOKEN_SUPPLY + 100));
+		System::assert_last_event(Event::Krypt(KryptEvent::SupplyCapSet(MAX_TOKEN_SUPPLY + 100)));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_eq!(Krypt::total_issued(), MAX_TOKEN_SUPPLY + 100);
+	});
+}
+
+#[test]
+fn supply_cap_cannot_go_below_issuance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 500, 1));
+		assert_noop!(Krypt::set_supply_cap(Origin::root(), 499), Error::<Test>::CapBelowIssued);
+		// Lowering it down to exactly what's issued is fine
+		assert_ok!(Krypt::set_supply_cap(Origin::root(), 500));
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 1, 1)),
+			Error::<Test>::MintCausingTotalSupplyOverflow
+		);
+	});
+}
+
+#[test]
+fn only_the_admin_origin_sets_the_supply_cap() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Krypt::set_supply_cap(Origin::signed(1), 10), DispatchError::BadOrigin);
+	});
+}
+
+#[test]
+fn transfer_over_a_self_cap_fails_without_a_sink() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::set_self_max_balance(Origin::signed(2), Some(50)));
+		System::assert_last_event(Event::Krypt(KryptEvent::SelfMaxBalanceSet(2, Some(50))));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 50));
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 1)),
+			Error::<Test>::SelfCapExceeded
+		);
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 1, 2)),
+			Error::<Test>::SelfCapExceeded
+		);
+		// Legs to the same recipient add up against its cap
+		assert_ok!(Krypt::set_self_max_balance(Origin::signed(2), Some(60)));
+		assert_noop!(
+			without_post_info(Krypt::transfer_batch(Origin::signed(1), vec![(2, 6), (2, 6)])),
+			Error::<Test>::SelfCapExceeded
+		);
+
+		// Clearing the cap lifts the limit
+		assert_ok!(Krypt::set_self_max_balance(Origin::signed(2), None));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 50));
+		assert_eq!(Krypt::get_balance_of(&2), 100);
+	});
+}
+
+#[test]
+fn transfer_over_a_self_cap_routes_the_excess_to_the_sink() {
+	new_test_ext().execute_with(|| {
+		SelfCapOverflowSink::set(Some(9));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::set_self_max_balance(Origin::signed(2), Some(50)));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 80));
+		assert_eq!(Krypt::get_balance_of(&1), 20);
+		assert_eq!(Krypt::get_balance_of(&2), 50);
+		assert_eq!(Krypt::get_balance_of(&9), 30);
+		System::assert_has_event(Event::Krypt(KryptEvent::SelfCapOverflowRouted(2, 9, 30)));
+
+		// Minted excess goes the same way without changing issuance
+		assert_ok!(Krypt::mint(Origin::root(), 10, 2));
+		assert_eq!(Krypt::get_balance_of(&9), 40);
+		assert_eq!(Krypt::total_issued(), 110);
+	});
+}
+
+#[test]
+fn account_status_reports_a_self_cap() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::set_self_max_balance(Origin::signed(2), Some(50)));
+		assert_eq!(
+			Krypt::account_status(&2),
+			AccountStatus {
+				frozen: false,
+				blocked: false,
+				paused: false,
+				cooldown: false,
+				capped: true
+			}
+		);
+		assert_ok!(Krypt::set_self_max_balance(Origin::signed(2), None));
+		assert!(Krypt::account_status(&2).is_unrestricted());
+	});
+}
+
+/// Rejected `mint`, `mint_batch`, `transfer_from` and `transfer_batch` calls report the weight they
+/// actually used, drop it to compare against plain errors
+fn without_post_info<T>(result: Result<T, DispatchErrorWithPostInfo>) -> Result<T, DispatchError> {
+	result.map_err(|error| error.error)
+}
+
+/// Finalize the current block and start the next one, as the executive would
+fn next_block() {
+	let now = System::block_number();
+	Krypt::on_finalize(now);
+	System::set_block_number(now + 1);
+	Krypt::on_initialize(now + 1);
+}
+
+#[test]
+fn inflation_rate_compares_against_a_recorded_snapshot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 1_000, 1));
+		// Snapshot of 1_000 at block 1
+		next_block();
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		next_block();
+		assert_ok!(Krypt::mint(Origin::root(), 150, 1));
+		// Block 3, 1_250 issued: 25% over block 1 and about 13.6% over block 2
+		assert_eq!(Krypt::inflation_rate(2), Perbill::from_percent(25));
+		assert_eq!(Krypt::inflation_rate(1), Perbill::from_rational(150u64, 1_100u64));
+		// Windows reaching past the oldest snapshot have nothing to compare against
+		assert_eq!(Krypt::inflation_rate(3), Perbill::zero());
+	});
+}
+
+#[test]
+fn supply_history_keeps_only_the_latest_snapshots() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..6 {
+			assert_ok!(Krypt::mint(Origin::root(), 10, 1));
+			next_block();
+		}
+		let history = Krypt::supply_history();
+		assert_eq!(history.len(), 4);
+		assert_eq!(history.first(), Some(&(3, 30)));
+		assert_eq!(history.last(), Some(&(6, 60)));
+	});
+}
+
+#[test]
+fn supply_history_skips_blocks_that_left_issuance_alone() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 10, 1));
+		next_block();
+		next_block();
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 5));
+		next_block();
+		assert_ok!(Krypt::mint(Origin::root(), 10, 1));
+		next_block();
+		assert_eq!(Krypt::supply_history().into_inner(), vec![(1, 10), (4, 20)]);
+		// The snapshot of block 1 still stands for the quiet blocks after it
+		assert_eq!(Krypt::inflation_rate(2), Perbill::from_percent(100));
+	});
+}
+
+#[test]
+fn transfer_unchecked_reports_insufficient_funds_instead_of_panicking() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		// Extrinsics check funds before getting here, so call it directly
+		assert_noop!(Krypt::transfer_unchecked(&1, &2, 101), Error::<Test>::InsufficientFunds);
+		assert_noop!(Krypt::transfer_unchecked(&3, &2, 1), Error::<Test>::InsufficientFunds);
+		assert_ok!(Krypt::transfer_unchecked(&1, &2, 100));
+		assert_eq!(Krypt::get_balance_of(&2), 100);
+	});
+}
+
+#[test]
+fn format_balance_without_decimals() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Krypt::format_balance(0), "0");
+		assert_eq!(Krypt::format_balance(1_500), "1500");
+		assert_eq!(Krypt::parse_balance("1500"), Ok(1_500));
+		assert!(Krypt::parse_balance("1.5").is_err());
+	});
+}
+
+#[test]
+fn format_balance_with_decimals_trims_trailing_zeros() {
+	new_test_ext().execute_with(|| {
+		Decimals::set(6);
+		assert_eq!(Krypt::format_balance(0), "0");
+		assert_eq!(Krypt::format_balance(1_500_000), "1.5");
+		assert_eq!(Krypt::format_balance(2_000_000), "2");
+		assert_eq!(Krypt::format_balance(1), "0.000001");
+		assert_eq!(Krypt::format_balance(12_345_678), "12.345678");
+	});
+}
+
+#[test]
+fn parse_balance_inverts_format_balance() {
+	new_test_ext().execute_with(|| {
+		Decimals::set(6);
+		for amount in [0, 1, 1_500_000, 2_000_000, 12_345_678] {
+			assert_eq!(Krypt::parse_balance(&Krypt::format_balance(amount)), Ok(amount));
+		}
+		assert_eq!(Krypt::parse_balance(".5"), Ok(500_000));
+		assert_eq!(Krypt::parse_balance("3."), Ok(3_000_000));
+		assert!(Krypt::parse_balance("").is_err());
+		assert!(Krypt::parse_balance("1.0000001").is_err());
+		assert!(Krypt::parse_balance("-1").is_err());
+		assert!(Krypt::parse_balance("1,5").is_err());
+		// Doesn't fit a u64
+		assert!(Krypt::parse_balance("18446744073709.551616").is_err());
+	});
+}
+
+#[test]
+fn accepted_offers_pay_the_recipient() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 2, 40));
+		System::assert_last_event(Event::Krypt(KryptEvent::TransferOffered(0, 1, 2, 40)));
+		// Escrowed funds left the sender but are still issued
+		assert_eq!(Krypt::get_balance_of(&1), 60);
+		assert_eq!(Krypt::get_balance_of(&2), 0);
+		assert_eq!(Krypt::total_issued(), 100);
+
+		assert_noop!(
+			Krypt::accept_transfer(Origin::signed(3), 0),
+			Error::<Test>::NotOfferRecipient
+		);
+		assert_ok!(Krypt::accept_transfer(Origin::signed(2), 0));
+		System::assert_last_event(Event::Krypt(KryptEvent::TransferAccepted(0)));
+		assert_eq!(Krypt::get_balance_of(&2), 40);
+		assert_eq!(Krypt::offer(0), None);
+		assert_noop!(Krypt::accept_transfer(Origin::signed(2), 0), Error::<Test>::OfferNotFound);
+	});
+}
+
+#[test]
+fn withdrawn_offers_go_back_to_the_sender() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 2, 40));
+		assert_noop!(Krypt::withdraw_offer(Origin::signed(2), 0), Error::<Test>::NotOfferSender);
+		assert_ok!(Krypt::withdraw_offer(Origin::signed(1), 0));
+		System::assert_last_event(Event::Krypt(KryptEvent::OfferWithdrawn(0)));
+		assert_eq!(Krypt::get_balance_of(&1), 100);
+		assert_noop!(Krypt::accept_transfer(Origin::signed(2), 0), Error::<Test>::OfferNotFound);
+	});
+}
+
+#[test]
+fn unaccepted_offers_expire_back_to_the_sender() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 2, 40));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 3, 10));
+		assert_ok!(Krypt::accept_transfer(Origin::signed(3), 1));
+		assert_eq!(Krypt::offer(0).map(|offer| offer.expires_at), Some(6));
+		// Offers can't go past the per-block limit
+		assert_noop!(Krypt::offer_transfer(Origin::signed(1), 2, 1), Error::<Test>::TooManyOffers);
+
+		// Still open the block before
+		for _ in 1..5 {
+			next_block();
+		}
+		assert!(Krypt::offer(0).is_some());
+		next_block();
+		assert_eq!(Krypt::offer(0), None);
+		System::assert_has_event(Event::Krypt(KryptEvent::OfferExpired(0)));
+		// Only the open offer is refunded
+		assert_eq!(Krypt::get_balance_of(&1), 90);
+		assert_eq!(Krypt::get_balance_of(&3), 10);
+	});
+}
+
+#[test]
+fn frozen_accounts_cannot_offer_transfers() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::freeze(Origin::root(), 1, vec![]));
+		assert_noop!(Krypt::offer_transfer(Origin::signed(1), 2, 40), Error::<Test>::AccountFrozen);
+		assert_noop!(
+			Krypt::offer_transfer(Origin::signed(3), 2, 1),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn offers_to_oneself_escrow_nothing() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(
+			Krypt::offer_transfer(Origin::signed(1), 1, 101),
+			Error::<Test>::InsufficientFunds
+		);
+		let events = System::events().len();
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 1, 40));
+		assert_eq!(Krypt::get_balance_of(&1), 100);
+		assert_eq!(Krypt::offer(0), None);
+		assert_eq!(System::events().len(), events);
+	});
+}
+
+#[test]
+fn a_frozen_owner_stops_spends_before_the_allowance_is_looked_at() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 10));
+		assert_ok!(Krypt::freeze(Origin::root(), 1, vec![]));
+		assert_noop!(Krypt::spend_from(Origin::signed(2), 1, 3, 20), Error::<Test>::AccountFrozen);
+	});
+}
+
+#[test]
+fn mint_into_an_account_near_the_type_maximum_fails() {
+	new_test_ext().execute_with(|| {
+		// Balances never add up to more than `TotalIssued`, so it sits near the maximum too
+		crate::BalanceToAccount::<Test>::insert(1, u64::MAX - 5);
+		crate::TotalIssued::<Test>::put(u64::MAX - 5);
+		crate::TotalMinted::<Test>::put(u64::MAX - 5);
+		crate::SupplyCap::<Test>::put(u64::MAX);
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 10, 1)),
+			Error::<Test>::MintTypeOverflow
+		);
+		assert_eq!(Krypt::get_balance_of(&1), u64::MAX - 5);
+		assert_ok!(Krypt::mint(Origin::root(), 5, 1));
+		assert_eq!(Krypt::get_balance_of(&1), u64::MAX);
+	});
+}
+
+#[test]
+fn transfer_into_an_account_near_the_type_maximum_changes_neither_balance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		crate::BalanceToAccount::<Test>::insert(2, u64::MAX - 5);
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 10)),
+			Error::<Test>::BalanceOverflow
+		);
+		// The unchecked path debits last as well
+		assert_noop!(Krypt::transfer_unchecked(&1, &2, 10), Error::<Test>::BalanceOverflow);
+		assert_eq!(Krypt::get_balance_of(&1), 100);
+		assert_eq!(Krypt::get_balance_of(&2), u64::MAX - 5);
+	});
+}
+
+#[test]
+fn saturating_mints_stop_at_the_type_maximum() {
+	new_test_ext().execute_with(|| {
+		SaturatingArithmetic::set(true);
+		crate::BalanceToAccount::<Test>::insert(1, u64::MAX - 5);
+		crate::TotalIssued::<Test>::put(u64::MAX - 5);
+		crate::TotalMinted::<Test>::put(u64::MAX - 5);
+		crate::SupplyCap::<Test>::put(u64::MAX);
+		assert_ok!(Krypt::mint(Origin::root(), 10, 1));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == Event::Krypt(KryptEvent::PartialMint(10, 5))));
+		assert_eq!(Krypt::get_balance_of(&1), u64::MAX);
+		assert_eq!(Krypt::total_issued(), u64::MAX);
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 1, 1)),
+			Error::<Test>::MintTypeOverflow
+		);
+	});
+}
+
+#[test]
+fn saturating_credits_destroy_what_does_not_fit() {
+	new_test_ext().execute_with(|| {
+		SaturatingArithmetic::set(true);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		crate::BalanceToAccount::<Test>::insert(2, u64::MAX - 5);
+		assert_ok!(Krypt::transfer_unchecked(&1, &2, 10));
+		assert_eq!(Krypt::get_balance_of(&1), 90);
+		assert_eq!(Krypt::get_balance_of(&2), u64::MAX);
+		// The 5 that didn't fit are gone
+		assert_eq!(Krypt::total_issued(), 95);
+	});
+}
+
+#[test]
+fn configured_minter_can_mint() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::signed(MINTER), 100, 1));
+		assert_eq!(Krypt::get_balance_of(&1), 100);
+		assert_eq!(Krypt::total_issued(), 100);
+		// Root still can, any other signer can't
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::signed(1), 100, 1)),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn listed_minters_can_mint_with_a_signed_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Krypt::add_minter(Origin::signed(1), 1), DispatchError::BadOrigin);
+		assert_ok!(Krypt::add_minter(Origin::root(), 1));
+		System::assert_last_event(Event::Krypt(KryptEvent::MinterAdded(1)));
+
+		assert_ok!(Krypt::mint(Origin::signed(1), 100, 2));
+		assert_ok!(Krypt::mint_whole(Origin::signed(1), 2, 10));
+		assert_ok!(Krypt::mint_batch(Origin::signed(1), vec![(3, 10), (4, 10)]));
+		assert_eq!(Krypt::get_balance_of(&2), 110);
+		assert_eq!(Krypt::total_issued(), 130);
+		// Listed minters are bound by the supply cap like everyone else
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::signed(1), MAX_TOKEN_SUPPLY, 2)),
+			Error::<Test>::MintCausingTotalSupplyOverflow
+		);
+	});
+}
+
+#[test]
+fn unlisted_signers_cannot_mint_but_the_admin_always_can() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::signed(2), 100, 2)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Krypt::add_minter(Origin::root(), 2));
+		assert_ok!(Krypt::remove_minter(Origin::root(), 2));
+		System::assert_last_event(Event::Krypt(KryptEvent::MinterRemoved(2)));
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::signed(2), 100, 2)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(Krypt::remove_minter(Origin::root(), 2), Error::<Test>::NotMinter);
+
+		// The admin origin mints without being listed
+		assert_ok!(Krypt::mint(Origin::root(), 100, 2));
+		assert_eq!(Krypt::get_balance_of(&2), 100);
+	});
+}
+
+#[test]
+fn migration_from_v0_writes_the_supply_cap_once() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<Krypt>();
+		crate::SupplyCap::<Test>::kill();
+
+		let weight = <Krypt as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(Krypt::on_chain_storage_version(), 2);
+		assert!(crate::SupplyCap::<Test>::exists());
+		assert_eq!(Krypt::supply_cap(), MAX_TOKEN_SUPPLY);
+		// v0 -> v1 writes the cap, v1 -> v2 finds no frozen accounts
+		assert_eq!(weight, <Test as frame_system::Config>::DbWeight::get().reads_writes(3, 3));
+
+		// Running it again only reads the version, once per step
+		let weight = <Krypt as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(weight, <Test as frame_system::Config>::DbWeight::get().reads(2));
+		assert_eq!(Krypt::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn migration_from_v1_keeps_accounts_frozen_with_an_empty_reason() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<Krypt>();
+		// v1 stored `()`, which encodes to nothing
+		frame_support::storage::unhashed::put_raw(
+			&crate::FrozenAccounts::<Test>::hashed_key_for(&1),
+			&[],
+		);
+
+		let weight = <Krypt as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(Krypt::on_chain_storage_version(), 2);
+		assert!(Krypt::is_frozen(&1));
+		assert_eq!(Krypt::freeze_reason(&1), Some(Default::default()));
+		assert_eq!(
+			weight,
+			<Test as frame_system::Config>::DbWeight::get()
+				.reads(1)
+				.saturating_add(<Test as frame_system::Config>::DbWeight::get().reads_writes(2, 2))
+		);
+	});
+}
+
+#[test]
+fn idle_sweep_removes_zero_allowances_across_several_blocks() {
+	new_test_ext().execute_with(|| {
+		// Leave dust reaping out of the weights below
+		ExistentialDeposit::set(0);
+		let db = <Test as frame_system::Config>::DbWeight::get();
+		for (owner, spender) in [(1, 2), (1, 3), (2, 3), (3, 4), (4, 1)] {
+			crate::Allowances::<Test>::insert(owner, spender, 0);
+		}
+		crate::AllowanceExpiries::<Test>::insert(1, 2, 100);
+		assert_ok!(Krypt::approve(Origin::signed(1), 4, 10));
+		assert_ok!(Krypt::approve(Origin::signed(2), 1, 5));
+
+		// Not even room for one entry
+		assert_eq!(Krypt::on_idle(1, db.reads_writes(2, 2)), 0);
+		assert_eq!(crate::Allowances::<Test>::iter().count(), 7);
+
+		// Three entries per block, so the seven of them take three blocks
+		let budget = db.reads_writes(2, 2) + 3 * db.reads_writes(1, 2);
+		assert_eq!(Krypt::on_idle(1, budget), budget);
+		assert_eq!(Krypt::on_idle(2, budget), budget);
+		assert!(!Krypt::zero_allowance_sweep_done());
+		// The last block only gets to one entry before running off the end
+		assert_eq!(Krypt::on_idle(3, budget), db.reads_writes(2, 2) + db.reads_writes(1, 2));
+		assert!(Krypt::zero_allowance_sweep_done());
+
+		assert_eq!(crate::Allowances::<Test>::iter().count(), 2);
+		assert_eq!(Krypt::allowance(&1, &4), 10);
+		assert_eq!(Krypt::allowance(&2, &1), 5);
+		assert!(!crate::AllowanceExpiries::<Test>::contains_key(1, 2));
+		assert!(crate::ZeroAllowanceSweepCursor::<Test>::get().is_none());
+		// Once done, idle blocks only read the flag
+		assert_eq!(Krypt::on_idle(4, budget), db.reads(1));
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn idle_reaping_burns_dust_accounts_across_several_blocks() {
+	new_test_ext().execute_with(|| {
+		let db = <Test as frame_system::Config>::DbWeight::get();
+		for (who, amount) in [(1, 5), (2, 50), (3, 3), (4, 9), (5, 100)] {
+			assert_ok!(Krypt::mint(Origin::root(), amount, who));
+		}
+		ExistentialDeposit::set(10);
+		// Keep the allowance sweep down to reading its flag
+		crate::ZeroAllowanceSweepDone::<Test>::put(true);
+
+		// Two accounts per block, so the five of them take three blocks
+		let budget = db.reads(1) + db.reads_writes(1, 1) + 2 * db.reads_writes(3, 5);
+		assert_eq!(Krypt::on_idle(1, budget), budget);
+		assert_eq!(Krypt::on_idle(2, budget), budget);
+		// The last block only gets to one account before running off the end
+		assert_eq!(
+			Krypt::on_idle(3, budget),
+			db.reads(1) + db.reads_writes(1, 1) + db.reads_writes(3, 5)
+		);
+		assert!(crate::DustSweepCursor::<Test>::get().is_none());
+
+		for who in [1, 3, 4] {
+			assert_eq!(Krypt::get_balance_of(&who), 0);
+		}
+		assert_eq!(Krypt::get_balance_of(&2), 50);
+		assert_eq!(Krypt::get_balance_of(&5), 100);
+		assert_eq!(Krypt::holder_count(), 2);
+		assert_eq!(Krypt::total_issued(), 150);
+		System::assert_has_event(Event::Krypt(KryptEvent::DustReaped { who: 4, amount: 9 }));
+		let reaped = System::events()
+			.into_iter()
+			.filter(|record| matches!(record.event, Event::Krypt(KryptEvent::DustReaped { .. })))
+			.count();
+		assert_eq!(reaped, 3);
+		assert_issuance_is_sum_of_holdings();
+	});
+}
+
+#[test]
+fn transfer_keep_alive_refuses_to_drop_below_the_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(
+			Krypt::transfer_keep_alive(Origin::signed(1), 2, 91),
+			Error::<Test>::KeepAlive
+		);
+		assert_noop!(
+			Krypt::transfer_keep_alive(Origin::signed(1), 2, 100),
+			Error::<Test>::KeepAlive
+		);
+		assert_ok!(Krypt::transfer_keep_alive(Origin::signed(1), 2, 90));
+		assert_eq!(Krypt::get_balance_of(&1), 10);
+		assert_eq!(Krypt::get_balance_of(&2), 90);
+	});
+}
+
+#[test]
+fn transfer_all_sweeps_and_reaps_the_sender() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_all(Origin::signed(1), 2, false));
+		assert_eq!(Krypt::get_balance_of(&1), 0);
+		assert_eq!(Krypt::get_balance_of(&2), 100);
+		assert_eq!(System::providers(&1), 0);
+		System::assert_last_event(Event::Krypt(KryptEvent::TransferSuccess(1, 2, 100)));
+		// Nothing left to sweep
+		assert_noop!(
+			Krypt::transfer_all(Origin::signed(1), 2, false),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn transfer_all_with_keep_alive_leaves_the_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_all(Origin::signed(1), 2, true));
+		assert_eq!(Krypt::get_balance_of(&1), 10);
+		assert_eq!(Krypt::get_balance_of(&2), 90);
+		assert_eq!(System::providers(&1), 1);
+		// Only the existential deposit is left, which keep-alive won't touch
+		assert_noop!(
+			Krypt::transfer_all(Origin::signed(1), 2, true),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn set_metadata_stores_name_and_symbol_within_the_bound() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Krypt::set_metadata(Origin::signed(1), b"Hodl".to_vec(), b"HODL".to_vec()),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Krypt::set_metadata(Origin::root(), b"Hodl".to_vec(), b"HODL".to_vec()));
+		assert_eq!(Krypt::name().into_inner(), b"Hodl".to_vec());
+		assert_eq!(Krypt::symbol().into_inner(), b"HODL".to_vec());
+	});
+}
+
+#[test]
+fn set_metadata_rejects_input_over_the_bound() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Krypt::set_metadata(Origin::root(), b"Hodler".to_vec(), b"HODL".to_vec()),
+			Error::<Test>::MetadataTooLong
+		);
+		assert_noop!(
+			Krypt::set_metadata(Origin::root(), b"Hodl".to_vec(), b"HODLR".to_vec()),
+			Error::<Test>::MetadataTooLong
+		);
+	});
+}
+
+#[test]
+fn storage_info_bounds_every_item() {
+	let info = <Krypt as StorageInfoTrait>::storage_info();
+	assert!(!info.is_empty());
+	for item in info {
+		assert!(
+			item.max_size.is_some(),
+			"{} has no max size",
+			String::from_utf8_lossy(&item.storage_name)
+		);
+	}
+}
+
+#[test]
+fn offchain_worker_publishes_supply_stats() {
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 250, 2));
+
+		// Off-interval blocks don't submit anything
+		Krypt::offchain_worker(StatsInterval::get() - 1);
+		assert!(pool_state.read().transactions.is_empty());
+
+		Krypt::offchain_worker(StatsInterval::get());
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+		let stats =
+			SupplyStatsSnapshot { holders: 2, largest_balance: 250, block: StatsInterval::get() };
+		let call = crate::Call::submit_supply_stats { stats: stats.clone() };
+		assert_eq!(tx.call, Call::Krypt(call.clone()));
+
+		// Dispatching the submitted call lands the stats in storage
+		assert!(Krypt::validate_unsigned(TransactionSource::Local, &call).is_ok());
+		assert_ok!(Krypt::submit_supply_stats(Origin::none(), stats.clone()));
+		assert_eq!(Krypt::supply_stats(), Some(stats));
+	});
+}
+
+#[test]
+fn supply_stats_only_come_from_the_local_node_and_never_go_back() {
+	new_test_ext().execute_with(|| {
+		let stats = SupplyStatsSnapshot { holders: 1, largest_balance: 10, block: 10 };
+		let call = crate::Call::submit_supply_stats { stats: stats.clone() };
+		assert_eq!(
+			Krypt::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into()
+		);
+		assert_noop!(
+			Krypt::submit_supply_stats(Origin::signed(1), stats.clone()),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(Krypt::submit_supply_stats(Origin::none(), stats));
+		assert_eq!(
+			Krypt::validate_unsigned(TransactionSource::Local, &call),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+#[test]
+fn supply_stats_in_a_block_are_only_checked_for_freshness() {
+	new_test_ext().execute_with(|| {
+		let stats = SupplyStatsSnapshot { holders: 1, largest_balance: 10, block: 10 };
+		let call = crate::Call::submit_supply_stats { stats: stats.clone() };
+		// Nothing but the local node gets the stats into the pool
+		assert_eq!(
+			Krypt::validate_unsigned(TransactionSource::InBlock, &call),
+			InvalidTransaction::Call.into()
+		);
+		// Building or importing the block only turns away stale ones
+		assert_ok!(Krypt::pre_dispatch(&call));
+		assert_ok!(Krypt::submit_supply_stats(Origin::none(), stats));
+		assert_eq!(Krypt::pre_dispatch(&call), Err(InvalidTransaction::Stale.into()));
+	});
+}
+
+#[test]
+fn expiring_allowance_can_be_spent_up_to_its_expiry_block() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::approve_with_expiry(Origin::signed(1), 2, 50, 3));
+		System::assert_last_event(Event::Krypt(KryptEvent::ExpiringApproval(1, 2, 50, 3)));
+		assert_eq!(Krypt::allowance_expiry(1, 2), Some(3));
+
+		System::set_block_number(3);
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 20));
+		assert_eq!(Krypt::get_balance_of(&3), 20);
+
+		System::set_block_number(4);
+		assert_noop!(
+			Krypt::spend_from(Origin::signed(2), 1, 3, 1),
+			Error::<Test>::AllowanceExpired
+		);
+	});
+}
+
+#[test]
+fn plain_approve_clears_a_previous_expiry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::approve_with_expiry(Origin::signed(1), 2, 50, 3));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 50));
+		assert_eq!(Krypt::allowance_expiry(1, 2), None);
+
+		System::set_block_number(10);
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 50));
+	});
+}
+
+#[test]
+fn supply_cap_reached_only_fires_on_the_exact_cap() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), MAX_TOKEN_SUPPLY - 1, 1));
+		assert!(!System::events()
+			.iter()
+			.any(|record| matches!(record.event, Event::Krypt(KryptEvent::SupplyCapReached(_)))));
+		assert_ok!(Krypt::mint(Origin::root(), 1, 2));
+		System::assert_last_event(Event::Krypt(KryptEvent::SupplyCapReached(MAX_TOKEN_SUPPLY)));
+	});
+}
+
+#[test]
+fn zero_amount_transfers_are_rejected_without_events() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		let events = System::events().len();
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 0)),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(
+			Krypt::transfer_keep_alive(Origin::signed(1), 2, 0),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(
+			Krypt::transfer_with_memo(Origin::signed(1), 2, 0, b"rent".to_vec()),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(
+			without_post_info(Krypt::transfer_batch(Origin::signed(1), vec![(2, 10), (3, 0)])),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(Krypt::offer_transfer(Origin::signed(1), 2, 0), Error::<Test>::ZeroAmount);
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 50));
+		assert_noop!(Krypt::spend_from(Origin::signed(2), 1, 3, 0), Error::<Test>::ZeroAmount);
+		assert_eq!(System::events().len(), events + 1);
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			Event::Krypt(KryptEvent::TransferSuccess(_, _, 0))
+		)));
+	});
+}
+
+#[cfg(not(feature = "derived-issuance"))]
+#[test]
+fn zero_amount_burns_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(Krypt::burn(Origin::signed(1), 0), Error::<Test>::ZeroAmount);
+		assert_noop!(Krypt::donate_to_burn(Origin::signed(1), 0), Error::<Test>::ZeroAmount);
+	});
+}
+
+#[test]
+fn issued_supply_counts_before_it_has_an_owner() {
+	new_test_ext().execute_with(|| {
+		let issued = Krypt::issue(300).unwrap();
+		assert_eq!(issued.peek(), 300);
+		assert_eq!(Krypt::total_issued(), 300);
+		assert_eq!(Krypt::holder_count(), 0);
+
+		assert!(Krypt::deposit_issued(&1, issued).is_ok());
+		assert_eq!(Krypt::get_balance_of(&1), 300);
+		assert_eq!(Krypt::total_issued(), 300);
+		System::assert_last_event(Event::Krypt(KryptEvent::MintedNewSupply {
+			to: 1,
+			amount: 300,
+			total_issued: 300,
+		}));
+	});
+}
+
+#[test]
+fn dropping_issued_supply_takes_it_back_out() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		drop(Krypt::issue(300).unwrap());
+		assert_eq!(Krypt::total_issued(), 100);
+		assert_eq!(Krypt::total_minted(), 100);
+	});
+}
+
+#[test]
+fn issue_respects_the_supply_cap() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), MAX_TOKEN_SUPPLY - 100, 1));
+		assert_eq!(
+			Krypt::issue(101).err(),
+			Some(Error::<Test>::MintCausingTotalSupplyOverflow.into())
+		);
+		let issued = Krypt::issue(100).unwrap();
+		assert_eq!(Krypt::total_issued(), MAX_TOKEN_SUPPLY);
+		assert_eq!(
+			Krypt::issue(1).err(),
+			Some(Error::<Test>::MintCausingTotalSupplyOverflow.into())
+		);
+		assert!(Krypt::deposit_issued(&2, issued).is_ok());
+	});
+}
+
+#[test]
+fn deposit_issued_hands_back_what_the_account_cannot_take() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::set_self_max_balance(Origin::signed(2), Some(50)));
+		let issued = Krypt::issue(80).unwrap();
+		let issued = Krypt::deposit_issued(&2, issued).unwrap_err();
+		assert_eq!(issued.peek(), 80);
+		assert_eq!(Krypt::get_balance_of(&2), 0);
+		assert!(Krypt::deposit_issued(&1, issued).is_ok());
+		assert_eq!(Krypt::get_balance_of(&1), 80);
+		assert_eq!(Krypt::total_issued(), 80);
+	});
+}
+
+#[test]
+fn fully_spent_allowance_leaves_no_storage_entry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::approve_with_expiry(Origin::signed(1), 2, 50, 10));
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 20));
+		assert!(crate::Allowances::<Test>::contains_key(1, 2));
+
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 30));
+		assert!(!crate::Allowances::<Test>::contains_key(1, 2));
+		assert!(!crate::AllowanceExpiries::<Test>::contains_key(1, 2));
+		assert_eq!(Krypt::allowance(1, 2), 0);
+	});
+}
+
+#[test]
+fn clear_allowance_revokes_every_term() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::approve_recurring(Origin::signed(1), 2, 50, 10));
+		assert_ok!(Krypt::clear_allowance(Origin::signed(1), 2));
+		System::assert_last_event(Event::Krypt(KryptEvent::Approval(1, 2, 0)));
+		assert!(!crate::Allowances::<Test>::contains_key(1, 2));
+		assert!(!crate::RecurringAllowances::<Test>::contains_key(1, 2));
+
+		assert_ok!(Krypt::approve_with_expiry(Origin::signed(1), 2, 50, 10));
+		assert_ok!(Krypt::clear_allowance(Origin::signed(1), 2));
+		assert!(!crate::AllowanceExpiries::<Test>::contains_key(1, 2));
+	});
+}
+
+#[cfg(not(feature = "derived-issuance"))]
+#[test]
+fn transfer_fee_is_rounded_down_at_tiny_amounts() {
+	new_test_ext().execute_with(|| {
+		TransferFeeRate::set(Permill::from_percent(10));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+
+		// A tenth of 9 rounds down to nothing, so the whole amount arrives
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 9));
+		assert_eq!(Krypt::get_balance_of(&2), 9);
+		assert_eq!(Krypt::total_issued(), 100);
+
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 19));
+		System::assert_has_event(Event::Krypt(KryptEvent::TransferFeeBurned { from: 1, fee: 1 }));
+		System::assert_has_event(Event::Krypt(KryptEvent::TransferSuccess(1, 2, 18)));
+		assert_eq!(Krypt::get_balance_of(&1), 72);
+		assert_eq!(Krypt::get_balance_of(&2), 27);
+		assert_eq!(Krypt::total_issued(), 99);
+
+		// Sending everything still works, the fee never adds to what is sent
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 72));
+		assert_eq!(Krypt::get_balance_of(&1), 0);
+		assert_eq!(Krypt::get_balance_of(&2), 27 + 65);
+		assert_eq!(Krypt::total_issued(), 92);
+	});
+}
+
+#[cfg(not(feature = "derived-issuance"))]
+#[test]
+fn transfer_fee_is_charged_on_every_transfer_path() {
+	new_test_ext().execute_with(|| {
+		TransferFeeRate::set(Permill::from_percent(10));
+		assert_ok!(Krypt::mint(Origin::root(), 1_000, 1));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 100));
+
+		assert_ok!(Krypt::transfer_with_memo(Origin::signed(1), 3, 100, b"rent".to_vec()));
+		assert_ok!(Krypt::transfer_batch(Origin::signed(1), vec![(3, 100), (4, 100)]));
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 100));
+		assert_ok!(Krypt::force_transfer(Origin::root(), 1, 3, 100));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 3, 100));
+		assert_ok!(Krypt::accept_transfer(Origin::signed(3), 0));
+		System::assert_has_event(Event::Krypt(KryptEvent::TransferSuccess(1, 3, 90)));
+
+		// Six transfers of 100, each of them losing a tenth to the fee
+		assert_eq!(Krypt::get_balance_of(&1), 400);
+		assert_eq!(Krypt::get_balance_of(&3), 450);
+		assert_eq!(Krypt::get_balance_of(&4), 90);
+		assert_eq!(Krypt::total_issued(), 940);
+		let fees = System::events()
+			.iter()
+			.filter(|record| {
+				record.event == Event::Krypt(KryptEvent::TransferFeeBurned { from: 1, fee: 10 })
+			})
+			.count();
+		assert_eq!(fees, 6);
+	});
+}
+
+/// Fees a 50% `TransferFeeRate` takes off transfers of 5 and 7 under `policy`, both of them
+/// exactly half a unit past a whole one
+#[cfg(not(feature = "derived-issuance"))]
+fn half_unit_fees(policy: RoundingPolicy) -> (u64, u64) {
+	new_test_ext().execute_with(|| {
+		Rounding::set(policy);
+		TransferFeeRate::set(Permill::from_percent(50));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 5));
+		let first = 5 - Krypt::get_balance_of(&2);
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 3, 7));
+		let second = 7 - Krypt::get_balance_of(&3);
+		// Whatever the rounding, the fee and what arrives add up to what was sent
+		assert_eq!(Krypt::get_balance_of(&1), 100 - 5 - 7);
+		assert_eq!(Krypt::total_issued(), 100 - first - second);
+		(first, second)
+	})
+}
+
+#[cfg(not(feature = "derived-issuance"))]
+#[test]
+fn transfer_fees_on_a_half_unit_follow_the_rounding_policy() {
+	assert_eq!(half_unit_fees(RoundingPolicy::Down), (2, 3));
+	assert_eq!(half_unit_fees(RoundingPolicy::Up), (3, 4));
+	// 2.5 and 3.5 both go to the even neighbour
+	assert_eq!(half_unit_fees(RoundingPolicy::NearestEven), (2, 4));
+}
+
+/// `DecayIndex` after a 50% decay shrinks it from 5 and from 7 under `policy`
+#[cfg(not(feature = "derived-issuance"))]
+fn half_unit_decay(policy: RoundingPolicy) -> (u128, u128) {
+	new_test_ext().execute_with(|| {
+		Rounding::set(policy);
+		DecayRatePerBlock::set(Permill::from_percent(50));
+		crate::DecayIndex::<Test>::put(5);
+		next_block();
+		let first = Krypt::decay_index();
+		crate::DecayIndex::<Test>::put(7);
+		next_block();
+		(first, Krypt::decay_index())
+	})
+}
+
+#[cfg(not(feature = "derived-issuance"))]
+#[test]
+fn decay_on_a_half_unit_follows_the_rounding_policy() {
+	assert_eq!(half_unit_decay(RoundingPolicy::Down), (2, 3));
+	assert_eq!(half_unit_decay(RoundingPolicy::Up), (3, 4));
+	assert_eq!(half_unit_decay(RoundingPolicy::NearestEven), (2, 4));
+}
+
+#[test]
+fn zero_transfer_fee_leaves_transfers_untouched() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 40));
+		assert_eq!(Krypt::get_balance_of(&2), 40);
+		assert_eq!(Krypt::total_issued(), 100);
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			Event::Krypt(KryptEvent::TransferFeeBurned { .. })
+		)));
+	});
+}
+
+#[cfg(feature = "derived-issuance")]
+#[test]
+fn transfer_fees_are_disabled_with_derived_issuance() {
+	new_test_ext().execute_with(|| {
+		TransferFeeRate::set(Permill::from_percent(10));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 10)),
+			Error::<Test>::BurnDisabled
+		);
+		// Amounts too small to carry a fee go through
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 9));
+	});
+}
+
+#[test]
+fn accounts_page_walks_every_holder_once() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=10 {
+			assert_ok!(Krypt::mint(Origin::root(), who * 10, who));
+		}
+
+		let mut seen = Vec::new();
+		let mut start_key = None;
+		loop {
+			let (page, next_key) = Krypt::accounts_page(start_key, 3);
+			assert!(page.len() <= 3);
+			seen.extend(page);
+			match next_key {
+				Some(key) => start_key = Some(key),
+				None => break,
+			}
+		}
+		seen.sort();
+		assert_eq!(seen, (1..=10).map(|who| (who, who * 10)).collect::<Vec<_>>());
+	});
+}
+
+#[test]
+fn accounts_page_is_bounded_by_max_accounts_page() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=10 {
+			assert_ok!(Krypt::mint(Origin::root(), 10, who));
+		}
+		let (page, next_key) = Krypt::accounts_page(None, 100);
+		assert_eq!(page.len(), MaxAccountsPage::get() as usize);
+		assert!(next_key.is_some());
+	});
+}
+
+#[test]
+fn balance_conversions_refuse_to_saturate() {
+	assert_eq!(Krypt::balance_to_u128(u64::MAX), Some(u64::MAX as u128));
+	assert_eq!(Krypt::u128_to_balance(u64::MAX as u128), Some(u64::MAX));
+	assert_eq!(Krypt::u128_to_balance(u64::MAX as u128 + 1), None);
+	assert_eq!(Krypt::u128_to_balance(u128::MAX), None);
+	assert_eq!(Krypt::u128_to_balance(0), Some(0));
+}
+
+#[test]
+fn accounts_cannot_approve_themselves() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Krypt::approve(Origin::signed(1), 1, 50), Error::<Test>::ApproveSelf);
+		assert_noop!(
+			Krypt::approve_with_expiry(Origin::signed(1), 1, 50, 10),
+			Error::<Test>::ApproveSelf
+		);
+		assert_noop!(
+			Krypt::approve_recurring(Origin::signed(1), 1, 50, 10),
+			Error::<Test>::ApproveSelf
+		);
+	});
+}
+
+#[test]
+fn transfer_to_self_is_a_no_op_that_still_checks_funds() {
+	new_test_ext().execute_with(|| {
+		TransferFeeRate::set(Permill::from_percent(10));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 1, 101)),
+			Error::<Test>::InsufficientFunds
+		);
+		let events = System::events().len();
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 1, 100));
+		assert_eq!(Krypt::get_balance_of(&1), 100);
+		assert_eq!(Krypt::total_issued(), 100);
+		assert_eq!(System::events().len(), events);
+	});
+}
+
+#[test]
+fn balance_cap_blocks_credits_over_the_limit() {
+	new_test_ext().execute_with(|| {
+		MaxBalancePerAccount::set(Some(500));
+		assert_ok!(Krypt::mint(Origin::root(), 300, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 400, 2));
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 101)),
+			Error::<Test>::BalanceCapExceeded
+		);
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 101, 2)),
+			Error::<Test>::BalanceCapExceeded
+		);
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 100));
+		assert_eq!(Krypt::get_balance_of(&2), 500);
+		assert!(Krypt::account_status(&2).capped);
+	});
+}
+
+#[test]
+fn exempt_accounts_bypass_the_balance_cap() {
+	new_test_ext().execute_with(|| {
+		MaxBalancePerAccount::set(Some(500));
+		assert_noop!(
+			Krypt::set_balance_cap_exempt(Origin::signed(2), 2, true),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Krypt::set_balance_cap_exempt(Origin::root(), 2, true));
+		System::assert_last_event(Event::Krypt(KryptEvent::BalanceCapExemptionSet(2, true)));
+		assert!(!Krypt::account_status(&2).capped);
+
+		assert_ok!(Krypt::mint(Origin::root(), 800, 2));
+		assert_eq!(Krypt::get_balance_of(&2), 800);
+
+		// Revoking the exemption leaves existing funds alone but blocks further credits
+		assert_ok!(Krypt::set_balance_cap_exempt(Origin::root(), 2, false));
+		assert_noop!(
+			without_post_info(Krypt::mint(Origin::root(), 1, 2)),
+			Error::<Test>::BalanceCapExceeded
+		);
+	});
+}
+
+#[test]
+fn redenominate_preserves_ratios() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 10, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 20, 2));
+		assert_ok!(Krypt::mint(Origin::root(), 30, 3));
+		assert_noop!(Krypt::redenominate(Origin::signed(1), 3, 1, 3, 0), DispatchError::BadOrigin);
+		assert_noop!(Krypt::redenominate(Origin::root(), 3, 0, 3, 0), Error::<Test>::InvalidRatio);
+
+		assert_ok!(Krypt::redenominate(Origin::root(), 3, 1, 3, 0));
+		System::assert_last_event(Event::Krypt(KryptEvent::Redenominated {
+			numerator: 3,
+			denominator: 1,
+		}));
+		assert_eq!(Krypt::get_balance_of(&1), 30);
+		assert_eq!(Krypt::get_balance_of(&2), 60);
+		assert_eq!(Krypt::get_balance_of(&3), 90);
+		assert_eq!(Krypt::total_issued(), 180);
+		assert_eq!(Krypt::supply_cap(), 3 * MAX_TOKEN_SUPPLY);
+	});
+}
+
+#[test]
+fn redenominate_recomputes_issuance_from_rounded_balances() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 1_999, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 1_999, 2));
+		assert_ok!(Krypt::mint(Origin::root(), 999, 3));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 2, 1_000));
+
+		assert_ok!(Krypt::redenominate(Origin::root(), 1, 1_000, 3, 1));
+		assert_eq!(Krypt::get_balance_of(&1), 0);
+		assert_eq!(Krypt::get_balance_of(&2), 1);
+		// Rounded down to nothing, so the account is gone
+		assert_eq!(Krypt::get_balance_of(&3), 0);
+		assert_eq!(Krypt::holder_count(), 1);
+		assert_eq!(Krypt::offer(0).unwrap().amount, 1);
+		// Scaling 4_997 blindly would give 4, but only 2 is actually held
+		assert_eq!(Krypt::total_issued(), 2);
+		assert_eq!(Krypt::supply_cap(), MAX_TOKEN_SUPPLY / 1_000);
+	});
+}
+
+#[test]
+fn redenominate_needs_witnesses_covering_every_entry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 2));
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 2, 10));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 10));
+		assert_ok!(Krypt::set_self_max_balance(Origin::signed(2), Some(1_000)));
+
+		assert_noop!(Krypt::redenominate(Origin::root(), 2, 1, 1, 3), Error::<Test>::WitnessTooLow);
+		// An offer, an allowance and a self-imposed cap
+		assert_noop!(Krypt::redenominate(Origin::root(), 2, 1, 2, 2), Error::<Test>::WitnessTooLow);
+		let weight = |holders, max_entries| {
+			crate::Call::<Test>::redenominate { numerator: 2, denominator: 1, holders, max_entries }
+				.get_dispatch_info()
+				.weight
+		};
+		assert!(weight(2, 3) < weight(2, 4));
+		assert!(weight(2, 3) < weight(3, 3));
+
+		assert_ok!(Krypt::redenominate(Origin::root(), 2, 1, 2, 3));
+		assert_eq!(Krypt::get_balance_of(&1), 180);
+		assert_eq!(Krypt::offer(0).unwrap().amount, 20);
+		assert_eq!(Krypt::allowance(&1, &2), 20);
+		assert_eq!(Krypt::self_max_balance(&2), Some(2_000));
+	});
+}
+
+#[test]
+fn redenominate_rescales_the_supply_history() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 10, 1));
+		next_block();
+		assert_ok!(Krypt::mint(Origin::root(), 10, 1));
+		next_block();
+		assert_ok!(Krypt::redenominate(Origin::root(), 3, 1, 1, 0));
+		assert_eq!(Krypt::supply_history().into_inner(), vec![(1, 30), (2, 60)]);
+		// Growth is measured the same in the new units
+		assert_eq!(Krypt::inflation_rate(2), Perbill::from_percent(100));
+	});
+}
+
+#[test]
+fn rescale_allowances_rounds_every_allowance_down() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 10));
+		assert_ok!(Krypt::approve(Origin::signed(1), 3, 25));
+		assert_ok!(Krypt::approve(Origin::signed(2), 3, 7));
+		assert_ok!(Krypt::approve_recurring(Origin::signed(3), 1, 50, 10));
+		assert_noop!(
+			Krypt::rescale_allowances(Origin::signed(1), 2, 3, 5),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Krypt::rescale_allowances(Origin::root(), 0, 3, 5),
+			Error::<Test>::InvalidRatio
+		);
+		// The recurring allowance also sets a one-off allowance, so five entries exist
+		assert_noop!(
+			Krypt::rescale_allowances(Origin::root(), 2, 3, 4),
+			Error::<Test>::TooManyAllowances
+		);
+
+		assert_ok!(Krypt::rescale_allowances(Origin::root(), 2, 3, 5));
+		System::assert_last_event(Event::Krypt(KryptEvent::AllowancesRescaled {
+			numerator: 2,
+			denominator: 3,
+			count: 5,
+		}));
+		// 20 / 3, 50 / 3, 14 / 3 and 100 / 3, all rounded down
+		assert_eq!(Krypt::allowance(&1, &2), 6);
+		assert_eq!(Krypt::allowance(&1, &3), 16);
+		assert_eq!(Krypt::allowance(&2, &3), 4);
+		assert_eq!(Krypt::allowance(&3, &1), 33);
+		assert_eq!(Krypt::recurring_allowance(&3, &1).unwrap().amount, 33);
+
+		// Scaling back up can't win back what rounding took
+		assert_ok!(Krypt::rescale_allowances(Origin::root(), 3, 2, 5));
+		assert_eq!(Krypt::allowance(&1, &2), 9);
+		assert_eq!(Krypt::allowance(&1, &3), 24);
+		assert_eq!(Krypt::allowance(&2, &3), 6);
+		assert_eq!(Krypt::recurring_allowance(&3, &1).unwrap().amount, 49);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn wipe_account_burns_the_balance_and_removes_its_storage() {
+	new_test_ext().execute_with(|| {
+		RecordAccountOrigin::set(true);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 30));
+		assert_ok!(Krypt::approve(Origin::signed(2), 3, 10));
+		assert_ok!(Krypt::approve(Origin::signed(3), 2, 10));
+		assert_ok!(Krypt::approve(Origin::signed(1), 3, 10));
+		assert_ok!(Krypt::approve_recurring(Origin::signed(1), 2, 10, 5));
+		assert_ok!(Krypt::set_self_max_balance(Origin::signed(2), Some(50)));
+		assert_ok!(Krypt::set_balance_cap_exempt(Origin::root(), 2, true));
+		assert_ok!(Krypt::freeze(Origin::root(), 2, b"sanctioned".to_vec()));
+		assert_noop!(Krypt::wipe_account(Origin::signed(1), 2, 5), DispatchError::BadOrigin);
+		// The recurring allowance also sets a one-off allowance, so five entries exist
+		assert_noop!(Krypt::wipe_account(Origin::root(), 2, 4), Error::<Test>::TooManyAllowances);
+
+		assert_ok!(Krypt::wipe_account(Origin::root(), 2, 5));
+		System::assert_last_event(Event::Krypt(KryptEvent::AccountWiped { who: 2, burned: 30 }));
+		assert_eq!(Krypt::get_balance_of(&2), 0);
+		assert_eq!(Krypt::total_issued(), 70);
+		assert_eq!(Krypt::holder_count(), 1);
+		assert_eq!(Krypt::allowance(&2, &3), 0);
+		assert_eq!(Krypt::allowance(&3, &2), 0);
+		assert_eq!(Krypt::allowance(&1, &2), 0);
+		assert_eq!(Krypt::recurring_allowance(&1, &2), None);
+		assert_eq!(Krypt::self_max_balance(&2), None);
+		assert!(!Krypt::is_frozen(&2));
+		assert!(!crate::BalanceCapExempt::<Test>::contains_key(&2));
+		assert_eq!(Krypt::account_origin(&2), None);
+		assert!(!crate::LastReceived::<Test>::contains_key(&2));
+		// Allowances between other accounts are left alone
+		assert_eq!(Krypt::allowance(&1, &3), 10);
+		assert_issuance_is_sum_of_holdings();
+	});
+}
+
+#[cfg(not(feature = "derived-issuance"))]
+fn assert_issuance_is_sum_of_holdings() {
+	let held = crate::BalanceToAccount::<Test>::iter_values()
+		.chain(crate::Offers::<Test>::iter_values().map(|offer| offer.amount))
+		.sum::<u64>();
+	assert_eq!(crate::TotalIssued::<Test>::get(), held);
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn balances_read_after_several_blocks_are_decayed() {
+	new_test_ext().execute_with(|| {
+		DecayRatePerBlock::set(Permill::from_percent(10));
+		System::set_block_number(1);
+		assert_ok!(Krypt::mint(Origin::root(), 1_000, 1));
+
+		next_block();
+		next_block();
+		next_block();
+		// 1_000 * 0.9^3, without the account having been touched since the mint
+		assert_eq!(Krypt::get_balance_of(&1), 729);
+		assert_eq!(Krypt::total_issued(), 729);
+		assert_eq!(Krypt::accounts_page(None, 4).0, vec![(1, 729)]);
+		assert_issuance_is_sum_of_holdings();
+
+		assert_noop!(Krypt::burn(Origin::signed(1), 730), Error::<Test>::InsufficientFunds);
+		assert_ok!(Krypt::burn(Origin::signed(1), 729));
+		assert_eq!(Krypt::total_issued(), 0);
+		assert_eq!(Krypt::holder_count(), 0);
+		assert_issuance_is_sum_of_holdings();
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn issuance_stays_the_sum_of_holdings_while_decaying() {
+	new_test_ext().execute_with(|| {
+		DecayRatePerBlock::set(Permill::from_percent(10));
+		System::set_block_number(1);
+		assert_ok!(Krypt::mint(Origin::root(), 1_000, 1));
+		next_block();
+
+		// Amounts moved after decay are worth exactly what was asked for
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 100));
+		assert_eq!(Krypt::get_balance_of(&2), 100);
+		assert_eq!(Krypt::get_balance_of(&1), 799);
+		assert_ok!(Krypt::offer_transfer(Origin::signed(1), 3, 50));
+		assert_ok!(Krypt::mint(Origin::root(), 10, 3));
+		assert_issuance_is_sum_of_holdings();
+
+		next_block();
+		next_block();
+		assert_ok!(Krypt::accept_transfer(Origin::signed(3), 0));
+		assert_ok!(Krypt::burn(Origin::signed(2), 7));
+		assert_issuance_is_sum_of_holdings();
+		// Each balance rounds down on its own, so together they may fall a little short
+		let balances = (1..=3).map(|who| Krypt::get_balance_of(&who)).sum::<u64>();
+		assert!(balances <= Krypt::total_issued());
+		assert!(Krypt::total_issued() - balances < 3);
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn balances_decayed_to_nothing_leave_issuance() {
+	new_test_ext().execute_with(|| {
+		DecayRatePerBlock::set(Permill::from_percent(50));
+		System::set_block_number(1);
+		assert_ok!(Krypt::mint(Origin::root(), 1, 1));
+		assert_ok!(Krypt::mint(Origin::root(), 4, 2));
+		next_block();
+
+		assert_eq!(Krypt::get_balance_of(&1), 0);
+		assert_eq!(Krypt::get_balance_of(&2), 2);
+		// The worthless remainder goes the next time the account is written
+		assert_ok!(Krypt::transfer_from(Origin::signed(2), 2, 1, 1));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 1));
+		assert_eq!(Krypt::holder_count(), 1);
+		assert_issuance_is_sum_of_holdings();
+	});
+}
+
+#[test]
+fn account_exists_until_the_balance_runs_out() {
+	new_test_ext().execute_with(|| {
+		assert!(!Krypt::account_exists(&1));
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert!(Krypt::account_exists(&1));
+		assert!(!Krypt::account_exists(&2));
+
+		assert_ok!(Krypt::transfer_all(Origin::signed(1), 2, false));
+		assert!(!Krypt::account_exists(&1));
+		assert!(Krypt::account_exists(&2));
+	});
+}
+
+#[test]
+#[cfg(not(feature = "derived-issuance"))]
+fn balance_changes_are_reported_with_old_and_new_balances() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 30));
+		assert_ok!(Krypt::burn(Origin::signed(2), 10));
+		// Rejected calls report nothing
+		assert_noop!(Krypt::burn(Origin::signed(2), 100), Error::<Test>::InsufficientFunds);
+		assert_eq!(BalanceChanges::get(), vec![(1, 0, 100), (1, 100, 70), (2, 0, 30), (2, 30, 20)]);
+	});
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Balances and open offers must add up to TotalIssued")]
+fn balances_poked_out_of_line_with_issuance_trip_the_check() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		crate::BalanceToAccount::<Test>::insert(2, 5);
+		let _ = Krypt::transfer_from(Origin::signed(1), 1, 3, 10);
+	});
+}
+
+#[test]
+fn spend_from_reports_the_remaining_allowance() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 50));
+
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 20));
+		System::assert_has_event(Event::Krypt(KryptEvent::TransferSuccess(1, 3, 20)));
+		System::assert_last_event(Event::Krypt(KryptEvent::SpentAllowance {
+			owner: 1,
+			spender: 2,
+			spent: 20,
+			remaining: 30,
+		}));
+		assert_eq!(Krypt::allowance(&1, &2), 30);
+	});
+}
+
+#[test]
+fn transfers_below_the_minimum_amount_are_rejected() {
+	new_test_ext().execute_with(|| {
+		MinTransferAmount::set(10);
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 50));
+
+		assert_noop!(
+			without_post_info(Krypt::transfer_from(Origin::signed(1), 1, 2, 9)),
+			Error::<Test>::BelowMinTransfer
+		);
+		assert_noop!(
+			Krypt::transfer_with_memo(Origin::signed(1), 2, 9, b"dust".to_vec()),
+			Error::<Test>::BelowMinTransfer
+		);
+		assert_noop!(
+			without_post_info(Krypt::transfer_batch(Origin::signed(1), vec![(2, 10), (3, 9)])),
+			Error::<Test>::BelowMinTransfer
+		);
+		assert_noop!(
+			Krypt::spend_from(Origin::signed(2), 1, 3, 9),
+			Error::<Test>::BelowMinTransfer
+		);
+		assert_noop!(
+			Krypt::offer_transfer(Origin::signed(1), 2, 9),
+			Error::<Test>::BelowMinTransfer
+		);
+
+		assert_ok!(Krypt::transfer_from(Origin::signed(1), 1, 2, 10));
+		assert_ok!(Krypt::transfer_batch(Origin::signed(1), vec![(2, 10), (3, 10)]));
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 10));
+		assert_eq!(Krypt::get_balance_of(&1), 60);
+	});
+}
+
+#[test]
+fn an_infinite_allowance_is_never_decremented() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Krypt::mint(Origin::root(), 100, 1));
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, u64::MAX));
+
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 20));
+		System::assert_last_event(Event::Krypt(KryptEvent::SpentAllowance {
+			owner: 1,
+			spender: 2,
+			spent: 20,
+			remaining: u64::MAX,
+		}));
+		assert_ok!(Krypt::spend_from(Origin::signed(2), 1, 3, 30));
+		assert_ok!(Krypt::transfer_from(Origin::signed(2), 1, 3, 40));
+		assert_eq!(Krypt::allowance(&1, &2), u64::MAX);
+		assert_eq!(Krypt::get_balance_of(&3), 90);
+
+		// Rescaling would turn it into a finite allowance, so it is left alone
+		assert_ok!(Krypt::approve(Origin::signed(1), 3, 30));
+		assert_ok!(Krypt::rescale_allowances(Origin::root(), 1, 3, 2));
+		assert_eq!(Krypt::allowance(&1, &2), u64::MAX);
+		assert_eq!(Krypt::allowance(&1, &3), 10);
+
+		// Approving a smaller amount replaces the sentinel outright
+		assert_ok!(Krypt::approve(Origin::signed(1), 2, 5));
+		assert_eq!(Krypt::allowance(&1, &2), 5);
+	});
+}
@@ -0,0 +1,192 @@
+//! State machine harness for mint, transfer, burn, approve and spend_from
+//! Random sequences of calls run against a model of the balances and allowances, and the
+//! pallet's invariants are checked after every call, see `check_invariants`
+//! Run it with `cargo test -p pallet-krypt state_machine`, and set `PROPTEST_CASES=10000` to dig
+//! deeper. Shrunk failing sequences belong in `REGRESSION_CORPUS`, so that they are replayed on
+//! every run
+
+use crate::{mock::*, BalanceToAccount};
+use frame_support::assert_ok;
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+/// Accounts the harness moves funds between
+const ACCOUNTS: u64 = 4;
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+	Mint { to: u64, amount: u64 },
+	Transfer { from: u64, to: u64, amount: u64 },
+	Burn { who: u64, amount: u64 },
+	Approve { owner: u64, spender: u64, amount: u64 },
+	SpendFrom { spender: u64, owner: u64, to: u64, amount: u64 },
+}
+
+/// What the pallet should hold after the calls that succeeded so far
+#[derive(Default)]
+struct Model {
+	balances: BTreeMap<u64, u64>,
+	allowances: BTreeMap<(u64, u64), u64>,
+	issued: u64,
+}
+
+impl Model {
+	fn balance(&self, who: u64) -> u64 {
+		self.balances.get(&who).copied().unwrap_or_default()
+	}
+
+	fn allowance(&self, owner: u64, spender: u64) -> u64 {
+		self.allowances.get(&(owner, spender)).copied().unwrap_or_default()
+	}
+
+	fn move_funds(&mut self, from: u64, to: u64, amount: u64) {
+		self.balances.insert(from, self.balance(from) - amount);
+		self.balances.insert(to, self.balance(to) + amount);
+	}
+
+	/// Dispatch `op` and update the model if it went through
+	fn apply(&mut self, op: Op) {
+		match op {
+			Op::Mint { to, amount } =>
+				if Krypt::mint(Origin::root(), amount, to).is_ok() {
+					self.balances.insert(to, self.balance(to) + amount);
+					self.issued += amount;
+				},
+			Op::Transfer { from, to, amount } =>
+				if Krypt::transfer_from(Origin::signed(from), from, to, amount).is_ok() {
+					self.move_funds(from, to, amount);
+				},
+			Op::Burn { who, amount } =>
+				if Krypt::burn(Origin::signed(who), amount).is_ok() {
+					self.balances.insert(who, self.balance(who) - amount);
+					self.issued -= amount;
+				},
+			Op::Approve { owner, spender, amount } =>
+				if Krypt::approve(Origin::signed(owner), spender, amount).is_ok() {
+					self.allowances.insert((owner, spender), amount);
+				},
+			Op::SpendFrom { spender, owner, to, amount } =>
+				if Krypt::spend_from(Origin::signed(spender), owner, to, amount).is_ok() {
+					let allowance = self.allowance(owner, spender);
+					if allowance != u64::MAX {
+						assert!(amount <= allowance, "{:?} spent more than was approved", op);
+						self.allowances.insert((owner, spender), allowance - amount);
+					}
+					self.move_funds(owner, to, amount);
+				},
+		}
+	}
+
+	/// Balances add up to `TotalIssued`, which stays under the cap, and every balance and
+	/// allowance is exactly what the successful calls so far left behind
+	fn check_invariants(&self, op: Op) {
+		let held = BalanceToAccount::<Test>::iter_values().sum::<u64>();
+		assert_eq!(held, Krypt::total_issued(), "balances drifted from TotalIssued after {:?}", op);
+		assert_eq!(Krypt::total_issued(), self.issued, "TotalIssued is off after {:?}", op);
+		assert!(Krypt::total_issued() <= Krypt::supply_cap(), "cap exceeded after {:?}", op);
+		for who in 1..=ACCOUNTS {
+			assert_eq!(Krypt::get_balance_of(&who), self.balance(who), "{} after {:?}", who, op);
+			for spender in 1..=ACCOUNTS {
+				assert_eq!(
+					Krypt::allowance(&who, &spender),
+					self.allowance(who, spender),
+					"allowance of {} to {} after {:?}",
+					who,
+					spender,
+					op
+				);
+			}
+		}
+	}
+}
+
+/// Run `ops` from genesis, checking the invariants after each of them
+fn run(ops: &[Op]) {
+	new_test_ext().execute_with(|| {
+		let mut model = Model::default();
+		for &op in ops {
+			model.apply(op);
+			model.check_invariants(op);
+		}
+	});
+}
+
+fn account() -> impl Strategy<Value = u64> {
+	1..=ACCOUNTS
+}
+
+/// Amounts that often hit an exact balance, allowance or the supply cap, and sometimes go past
+/// `u64::MAX` approves an infinite allowance
+fn amount() -> impl Strategy<Value = u64> {
+	prop_oneof![0..1_000u64, Just(MAX_TOKEN_SUPPLY), 0..MAX_TOKEN_SUPPLY * 2, Just(u64::MAX)]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+	prop_oneof![
+		(account(), amount()).prop_map(|(to, amount)| Op::Mint { to, amount }),
+		(account(), account(), amount()).prop_map(|(from, to, amount)| Op::Transfer {
+			from,
+			to,
+			amount
+		}),
+		(account(), amount()).prop_map(|(who, amount)| Op::Burn { who, amount }),
+		(account(), account(), amount()).prop_map(|(owner, spender, amount)| Op::Approve {
+			owner,
+			spender,
+			amount
+		}),
+		(account(), account(), account(), amount()).prop_map(|(spender, owner, to, amount)| {
+			Op::SpendFrom { spender, owner, to, amount }
+		}),
+	]
+}
+
+proptest! {
+	#[test]
+	fn random_call_sequences_keep_invariants(ops in prop::collection::vec(op(), 1..64)) {
+		run(&ops);
+	}
+}
+
+/// Sequences known to have broken the pallet at some point, replayed on every run
+const REGRESSION_CORPUS: &[&[Op]] = &[
+	// Minting to an account that already held funds overwrote its balance
+	&[Op::Mint { to: 1, amount: 100 }, Op::Mint { to: 1, amount: 50 }],
+	// Crediting the recipient overwrote its balance instead of adding to it
+	&[
+		Op::Mint { to: 1, amount: 100 },
+		Op::Mint { to: 2, amount: 100 },
+		Op::Transfer { from: 1, to: 2, amount: 40 },
+	],
+	// A transfer to oneself credited the old balance on top of the debited one
+	&[Op::Mint { to: 1, amount: 100 }, Op::Transfer { from: 1, to: 1, amount: 60 }],
+	// Burning didn't take the amount back out of TotalIssued
+	&[
+		Op::Mint { to: 1, amount: 100 },
+		Op::Burn { who: 1, amount: 100 },
+		Op::Mint { to: 1, amount: 1 },
+	],
+	// Minting right up to the cap, then past it
+	&[Op::Mint { to: 1, amount: MAX_TOKEN_SUPPLY }, Op::Mint { to: 2, amount: 1 }],
+	// Spending more than was approved, and then spending an allowance down to zero
+	&[
+		Op::Mint { to: 1, amount: 100 },
+		Op::Approve { owner: 1, spender: 2, amount: 30 },
+		Op::SpendFrom { spender: 2, owner: 1, to: 3, amount: 31 },
+		Op::SpendFrom { spender: 2, owner: 1, to: 3, amount: 30 },
+		Op::SpendFrom { spender: 2, owner: 1, to: 3, amount: 1 },
+	],
+	// An allowance larger than the owner's balance
+	&[
+		Op::Mint { to: 1, amount: 10 },
+		Op::Approve { owner: 1, spender: 2, amount: 100 },
+		Op::SpendFrom { spender: 2, owner: 1, to: 2, amount: 20 },
+	],
+];
+
+#[test]
+fn regression_corpus_keeps_invariants() {
+	for ops in REGRESSION_CORPUS {
+		run(ops);
+	}
+}
@@ -1,32 +1,162 @@
 //! An implementation of the Currency and Imbalance traits with limited tokens
-//! MaxTokenSupply imposes a hard upper limit on the number of tokens 
+//! MaxTokenSupply imposes a hard upper limit on the number of tokens
+//!
+//! Runtimes can expose an account's locks, reserves and account data to dashboards and RPC
+//! clients through a runtime API:
+//!
+//! ```ignore
+//! sp_api::decl_runtime_apis! {
+//!     pub trait KryptoKurrencyBalances {
+//!         fn locks_kryptokurrency_api(who: AccountId) -> Vec<(LockIdentifier, Balance)>;
+//!         fn reserved_kryptokurrency_api(who: AccountId) -> Balance;
+//!         fn account_data_kryptokurrency_api(who: AccountId) -> AccountData<Balance>;
+//!     }
+//! }
+//!
+//! impl_runtime_apis! {
+//!     impl crate::KryptoKurrencyBalances<Block> for Runtime {
+//!         fn locks_kryptokurrency_api(who: AccountId) -> Vec<(LockIdentifier, Balance)> {
+//!             KryptoKurrency::lock_summary(&who)
+//!         }
+//!
+//!         fn reserved_kryptokurrency_api(who: AccountId) -> Balance {
+//!             KryptoKurrency::reserved_balance(&who)
+//!         }
+//!
+//!         fn account_data_kryptokurrency_api(who: AccountId) -> AccountData<Balance> {
+//!             KryptoKurrency::account_data(&who)
+//!         }
+//!     }
+//! }
+//! ```
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
 
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use codec::{Codec, MaxEncodedLen};
-	use frame_support::traits::{Currency, Imbalance, TryDrop};
-	use frame_support::{pallet_prelude::*, RuntimeDebug};
+	use core::convert::Infallible;
+	use frame_support::{
+		pallet_prelude::*,
+		storage::weak_bounded_vec::WeakBoundedVec,
+		traits::{
+			tokens::{fungible, DepositConsequence, WithdrawConsequence},
+			BalanceStatus, Currency, ExistenceRequirement, GetStorageVersion, Imbalance,
+			LockIdentifier, LockableCurrency, NamedReservableCurrency, OnUnbalanced,
+			ReservableCurrency, SameOrOther, SignedImbalance, StorageVersion, TryDrop,
+			WithdrawReasons,
+		},
+		RuntimeDebug,
+	};
 	use frame_system::pallet_prelude::*;
 	use scale_info::TypeInfo;
-	use sp_runtime::traits::{
-		AtLeast32BitUnsigned, CheckedAdd, CheckedSub, Saturating, StaticLookup, Zero,
+	use sp_runtime::{
+		traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedSub, Saturating, StaticLookup, Zero},
+		SaturatedConversion,
 	};
-	use sp_std::{fmt::Debug, iter::Sum};
+	use sp_std::{cmp, fmt::Debug, iter::Sum, mem, vec::Vec};
 
 	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		MintedNewSupply(T::Balance),
+		/// Some funds were slashed from an account (who, amount)
+		Slashed(T::AccountId, T::Balance),
+		/// Vesting lock of an account shrank to a new amount (who, still locked)
+		VestingUpdated(T::AccountId, T::Balance),
+		/// Account has no more funds left vesting
+		VestingCompleted(T::AccountId),
+		/// Free balance of an account was forced by the admin origin
+		BalanceSet {
+			who: T::AccountId,
+			free: T::Balance,
+		},
+		/// Dust reaped from an account was burned (who, amount)
+		DustLost(T::AccountId, T::Balance),
+		/// Funds moved between accounts outside of a plain transfer, e.g. reaped dust going to
+		/// `DustTrap` (from, to, amount)
+		Transfer(T::AccountId, T::AccountId, T::Balance),
+		/// Funds were deposited into an account through `Currency`, outside of a transfer
+		Deposit {
+			who: T::AccountId,
+			amount: T::Balance,
+		},
+		/// Funds were withdrawn from an account through `Currency`, outside of a transfer
+		Withdraw {
+			who: T::AccountId,
+			amount: T::Balance,
+		},
+		/// A positive imbalance was settled and grew the supply (amount, new total issuance)
+		Issued(T::Balance, T::Balance),
+		/// A negative imbalance was settled and shrank the supply (amount, new total issuance)
+		Rescinded(T::Balance, T::Balance),
+		/// Funds were reserved for a new dispute
+		DisputeOpened {
+			id: u32,
+			opener: T::AccountId,
+			counterparty: T::AccountId,
+			amount: T::Balance,
+		},
+		/// Admin origin settled a dispute, `awarded` is what the winner actually received
+		DisputeResolved {
+			id: u32,
+			winner: T::AccountId,
+			awarded: T::Balance,
+		},
+		/// Balance breakdown of an account requested through `report_balance`
+		/// `locked` is the reserved balance, `usable` the part of `free` no lock holds
+		BalanceReport {
+			who: T::AccountId,
+			free: T::Balance,
+			locked: T::Balance,
+			usable: T::Balance,
+		},
 	}
 
+	/// Version of the storage layout, bumped alongside every migration
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	#[pallet::generate_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(PhantomData<T>);
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_to_v1()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Self::on_chain_storage_version() <= 1,
+				"Unknown kryptokurrency storage version"
+			);
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Self::on_chain_storage_version() == 1,
+				"kryptokurrency storage wasn't migrated to v1"
+			);
+			let held = AccountStore::<T>::iter_values()
+				.fold(T::Balance::zero(), |acc, account| acc.saturating_add(account.total()));
+			ensure!(held <= Self::total_issuance(), "Accounts hold more than TotalIssuance");
+			Ok(())
+		}
+	}
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		/// The Event type
@@ -46,49 +176,259 @@ pub mod pallet {
 			+ Copy
 			+ MaxEncodedLen;
 		/// Maximum number of Tokens possible in this Chain
+		/// Every deposit minting new funds is limited by it. Code moving funds that already
+		/// exist should use `resolve_creating` or `resolve_into_existing`, which aren't
 		#[pallet::constant]
 		type MaxTokenSupply: Get<Self::Balance>;
 		#[pallet::constant]
 		type ExistentialDeposit: Get<Self::Balance>;
+		/// The identifier used to tell named reserves apart
+		type ReserveIdentifier: Parameter + Member + MaxEncodedLen + Ord + Copy;
+		/// Maximum number of named reserves a single account can hold
+		#[pallet::constant]
+		type MaxReserves: Get<u32>;
+		/// Maximum number of balance locks a single account should hold
+		#[pallet::constant]
+		type MaxLocks: Get<u32>;
+		/// Origin allowed to perform administrative actions such as forcing balances
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+		/// Handler for the imbalance left over by minting into an account
+		/// `()` drops it, which adds the minted amount to `TotalIssuance`
+		type MintHandler: OnUnbalanced<PositiveImbalance<Self>>;
+		/// Handler for slashed funds, e.g. a treasury
+		/// `()` drops them, which burns them out of `TotalIssuance`
+		type SlashHandler: OnUnbalanced<NegativeImbalance<Self>>;
+		/// Account collecting dust left behind by reaped accounts
+		/// `None` burns the dust out of `TotalIssuance` instead
+		type DustTrap: Get<Option<Self::AccountId>>;
+		/// Named reserve backing the holds placed through `fungible::MutateHold`
+		#[pallet::constant]
+		type HoldReserveId: Get<Self::ReserveIdentifier>;
+		/// Named reserve backing the funds put up by `open_dispute`
+		#[pallet::constant]
+		type DisputeReserveId: Get<Self::ReserveIdentifier>;
 	}
 
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Account doesn't have enough free balance for the operation
+		InsufficientBalance,
+		/// Operation requires an account that already exists
+		DeadAccount,
+		/// Value too low to create an account, it must be at least `ExistentialDeposit`
+		ExistentialDeposit,
+		/// Crediting an account would overflow its balance
+		Overflow,
+		/// Account already holds `MaxReserves` named reserves
+		TooManyReserves,
+		/// Withdrawal would dip into funds held by a balance lock
+		LiquidityRestrictions,
+		/// Withdrawal would kill an account that must be kept alive
+		KeepAlive,
+		/// Account has no vesting schedule
+		NotVesting,
+		/// Account already has a vesting schedule
+		ExistingVestingSchedule,
+		/// Vesting schedules need a nonzero amount released per block
+		InvalidVestingSchedule,
+		/// Operation would take `TotalIssuance` over `MaxTokenSupply`
+		MaxTokenSupplyExceeded,
+		/// Accounts can't open a dispute against themselves
+		DisputeWithSelf,
+		/// No open dispute has this id
+		UnknownDispute,
+		/// Winner of a dispute must be one of its two parties
+		NotDisputeParty,
+	}
+
+	/// Hasher of every map keyed by account, `Twox64Concat` with the `trusted-account-ids`
+	/// feature. It works the same way as krypt's `AccountHasher`, see there for the tradeoff
+	#[cfg(not(feature = "trusted-account-ids"))]
+	pub type AccountHasher = Blake2_128Concat;
+	#[cfg(feature = "trusted-account-ids")]
+	pub type AccountHasher = Twox64Concat;
+
 	/// Account -> Balance map
 	#[pallet::storage]
 	#[pallet::getter(fn account_of)]
 	pub type AccountStore<T: Config> =
-		StorageMap<_, Blake2_128Concat, T::AccountId, AccountData<T::Balance>>;
+		StorageMap<_, AccountHasher, T::AccountId, AccountData<T::Balance>>;
 
 	/// Account details for some AccountId
 	#[derive(
 		Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, MaxEncodedLen, TypeInfo,
 	)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 	pub struct AccountData<Balance> {
 		pub free: Balance,
 		pub locked: Balance,
 	}
-	#[allow(unused)]
 	impl<Balance: Copy + Ord + Saturating> AccountData<Balance> {
 		/// Returns free balance
-		fn free(&self) -> Balance {
+		pub fn free(&self) -> Balance {
 			self.free
 		}
-		fn total(&self) -> Balance {
+		/// Returns free and reserved balance together
+		pub fn total(&self) -> Balance {
 			self.free.saturating_add(self.locked)
 		}
-		fn locked(&self) -> Balance {
+		/// Returns reserved balance
+		pub fn locked(&self) -> Balance {
 			self.locked
 		}
 	}
-	
+
+	/// A single named reserve held on an account
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct ReserveData<ReserveIdentifier, Balance> {
+		pub id: ReserveIdentifier,
+		pub amount: Balance,
+	}
+
+	/// Named reserves of an account, sorted by id
+	/// The sum of all named reserves never exceeds the account's `locked` balance
+	#[pallet::storage]
+	#[pallet::getter(fn reserves_of)]
+	pub type ReservesOf<T: Config> = StorageMap<
+		_,
+		AccountHasher,
+		T::AccountId,
+		BoundedVec<ReserveData<T::ReserveIdentifier, T::Balance>, T::MaxReserves>,
+		ValueQuery,
+	>;
+
+	/// Which kind of withdrawals a balance lock applies to
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub enum Reasons {
+		/// Only transaction fee payment
+		Fee = 0,
+		/// Anything but transaction fee payment
+		Misc = 1,
+		/// Every kind of withdrawal
+		All = 2,
+	}
+
+	impl From<WithdrawReasons> for Reasons {
+		fn from(reasons: WithdrawReasons) -> Reasons {
+			if reasons == WithdrawReasons::TRANSACTION_PAYMENT {
+				Reasons::Fee
+			} else if reasons.contains(WithdrawReasons::TRANSACTION_PAYMENT) {
+				Reasons::All
+			} else {
+				Reasons::Misc
+			}
+		}
+	}
+
+	impl Reasons {
+		/// Whether a withdrawal for `reasons` is restricted by a lock with these reasons
+		fn applies_to(&self, reasons: WithdrawReasons) -> bool {
+			match self {
+				Reasons::Fee => reasons.contains(WithdrawReasons::TRANSACTION_PAYMENT),
+				Reasons::Misc => !(reasons - WithdrawReasons::TRANSACTION_PAYMENT).is_empty(),
+				Reasons::All => true,
+			}
+		}
+
+		fn merge(self, other: Reasons) -> Reasons {
+			if self == other {
+				self
+			} else {
+				Reasons::All
+			}
+		}
+	}
+
+	/// A single balance lock, keeping `amount` of the free balance from being withdrawn
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct BalanceLock<Balance> {
+		pub id: LockIdentifier,
+		pub amount: Balance,
+		pub reasons: Reasons,
+	}
+
+	/// Balance locks of an account
+	/// Locks overlap rather than stack, so the largest applicable lock is what counts
+	#[pallet::storage]
+	#[pallet::getter(fn locks)]
+	pub type Locks<T: Config> = StorageMap<
+		_,
+		AccountHasher,
+		T::AccountId,
+		WeakBoundedVec<BalanceLock<T::Balance>, T::MaxLocks>,
+		ValueQuery,
+	>;
+
+	/// Lock identifier used by vesting schedules
+	const VESTING_ID: LockIdentifier = *b"vesting ";
+
+	/// Linear vesting schedule, releasing `per_block` of `locked` every block from
+	/// `starting_block` on
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct VestingInfo<Balance, BlockNumber> {
+		/// Amount locked at the start of the schedule
+		pub locked: Balance,
+		/// Amount released every block
+		pub per_block: Balance,
+		/// Block at which releasing starts
+		pub starting_block: BlockNumber,
+	}
+
+	impl<Balance: AtLeast32BitUnsigned + Copy, BlockNumber: AtLeast32BitUnsigned + Copy>
+		VestingInfo<Balance, BlockNumber>
+	{
+		/// Amount still locked at block `n`
+		pub fn locked_at(&self, n: BlockNumber) -> Balance {
+			let elapsed: Balance =
+				n.saturating_sub(self.starting_block).saturated_into::<u128>().saturated_into();
+			self.locked.saturating_sub(self.per_block.saturating_mul(elapsed))
+		}
+	}
+
+	/// Vesting schedule of an account, if any
+	#[pallet::storage]
+	#[pallet::getter(fn vesting)]
+	pub type Vesting<T: Config> =
+		StorageMap<_, AccountHasher, T::AccountId, VestingInfo<T::Balance, T::BlockNumber>>;
+
+	/// Funds reserved by `open_dispute` until the admin origin settles who gets them
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct Dispute<AccountId, Balance> {
+		/// Account whose funds are reserved
+		pub opener: AccountId,
+		/// Account the funds may be awarded to instead
+		pub counterparty: AccountId,
+		pub amount: Balance,
+	}
+
+	/// Id handed out to the next dispute
+	#[pallet::storage]
+	#[pallet::getter(fn next_dispute_id)]
+	pub type NextDisputeId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Mapping of dispute id -> open dispute
+	#[pallet::storage]
+	#[pallet::getter(fn dispute)]
+	pub type Disputes<T: Config> =
+		StorageMap<_, Twox64Concat, u32, Dispute<T::AccountId, T::Balance>>;
 
 	/// Storage for Total Issuance
+	/// Migration note: this used to be an `OptionQuery`. The encoded value is the same,
+	/// an absent key now simply reads as zero, so no storage migration is required
 	#[pallet::storage]
 	#[pallet::getter(fn total_issuance)]
-	pub type TotalIssuance<T: Config> = StorageValue<_, T::Balance>;
+	pub type TotalIssuance<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
 
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
-		pub balances: Vec<(T::AccountId, T::Balance)>,
+		/// Genesis endowments as (account, free, locked)
+		/// `locked` only sets the reserved part of the account, the one `ReservableCurrency`
+		/// works with. Despite the name it isn't a balance lock, no `LockableCurrency` lock is
+		/// placed at genesis
+		pub balances: Vec<(T::AccountId, T::Balance, T::Balance)>,
 		pub max_token_supply: Option<T::Balance>,
 	}
 
@@ -111,10 +451,13 @@ pub mod pallet {
 	#[pallet::genesis_build]
 	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
-			let total_issuance_at_genesis: T::Balance = self
-				.balances
-				.iter()
-				.fold(Zero::zero(), |acc: T::Balance, &(_, curr)| acc + curr);
+			let total_issuance_at_genesis: T::Balance =
+				self.balances.iter().fold(Zero::zero(), |acc: T::Balance, &(_, free, locked)| {
+					// Locked funds are part of the issuance just like free ones
+					acc.checked_add(&free)
+						.and_then(|acc| acc.checked_add(&locked))
+						.expect("genesis balances overflow Balance type")
+				});
 			let max_tokens_at_genesis: T::Balance = match self.max_token_supply {
 				Some(t) => t,
 				None => <Self as Default>::default().max_token_supply.unwrap(),
@@ -128,7 +471,7 @@ pub mod pallet {
 			let endowed_accounts = self
 				.balances
 				.iter()
-				.map(|(acc, _)| acc)
+				.map(|(acc, _, _)| acc)
 				.cloned()
 				.collect::<std::collections::BTreeSet<_>>();
 
@@ -137,12 +480,13 @@ pub mod pallet {
 				"Duplicate entries for accounts in genesis"
 			);
 
-			self.balances.iter().for_each(|&(ref who, ref free)| {
+			self.balances.iter().for_each(|&(ref who, ref free, ref locked)| {
 				AccountStore::<T>::insert(
 					who.clone(),
-					AccountData { free: free.clone(), ..Default::default() },
+					AccountData { free: free.clone(), locked: locked.clone() },
 				)
 			});
+			// Issuance starts out as exactly the genesis endowments
 			TotalIssuance::<T>::put(total_issuance_at_genesis);
 		}
 	}
@@ -150,7 +494,7 @@ pub mod pallet {
 	mod imbalance {
 		use super::{Config, Imbalance, RuntimeDebug, Saturating, TryDrop, Zero};
 		use core::{cmp::Ordering, result::Result};
-		use frame_support::traits::{Get, SameOrOther};
+		use frame_support::traits::SameOrOther;
 		use sp_std::mem;
 
 		#[derive(RuntimeDebug, PartialEq, Eq)]
@@ -160,11 +504,20 @@ pub mod pallet {
 		pub struct NegativeImbalance<T: Config>(<T as Config>::Balance);
 
 		impl<T: Config> PositiveImbalance<T> {
-			pub(super) fn new(amount: T::Balance) -> Self {
+			/// Only for code in this crate that has already moved the matching funds
+			pub(crate) fn new(amount: T::Balance) -> Self {
 				PositiveImbalance(amount)
 			}
 		}
 
+		#[cfg(test)]
+		impl<T: Config> PositiveImbalance<T> {
+			/// An imbalance of `amount` that no balance change backs, for tests
+			pub(crate) fn for_test(amount: T::Balance) -> Self {
+				Self::new(amount)
+			}
+		}
+
 		impl<T: Config> Default for PositiveImbalance<T> {
 			fn default() -> Self {
 				// Imbalance method
@@ -180,25 +533,41 @@ pub mod pallet {
 			}
 		}
 		/// Some amount was minted and as a result a PositiveImbalance was returned
-		/// Increase TotalIssuance by amount until MaxTokenSupply is hit
+		/// Increase TotalIssuance by amount. Every path minting new funds only hands out what
+		/// still fits under MaxTokenSupply, so this never goes past it as long as the
+		/// imbalance is dropped before the next mint
 		impl<T: Config> Drop for PositiveImbalance<T> {
 			fn drop(&mut self) {
-				super::TotalIssuance::<T>::mutate(|key| {
-					if let Some(total) = *key {
-						total.saturating_add(self.0);
-						total
-					} else {
-						T::MaxTokenSupply::get()
-					}
+				#[cfg(all(debug_assertions, feature = "strict-imbalances"))]
+				assert!(
+					self.0.is_zero(),
+					"Nonzero PositiveImbalance dropped without being handled"
+				);
+				if self.0.is_zero() {
+					return
+				}
+				let total = super::TotalIssuance::<T>::mutate(|total| {
+					*total = total.saturating_add(self.0);
+					*total
 				});
+				super::Pallet::<T>::deposit_event(super::Event::Issued(self.0, total));
 			}
 		}
 		impl<T: Config> NegativeImbalance<T> {
-			pub(super) fn new(amount: T::Balance) -> Self {
+			/// Only for code in this crate that has already moved the matching funds
+			pub(crate) fn new(amount: T::Balance) -> Self {
 				NegativeImbalance(amount)
 			}
 		}
 
+		#[cfg(test)]
+		impl<T: Config> NegativeImbalance<T> {
+			/// An imbalance of `amount` that no balance change backs, for tests
+			pub(crate) fn for_test(amount: T::Balance) -> Self {
+				Self::new(amount)
+			}
+		}
+
 		impl<T: Config> Default for NegativeImbalance<T> {
 			fn default() -> Self {
 				// Imbalance method
@@ -218,14 +587,19 @@ pub mod pallet {
 		/// Note: This doesn't not affect MaxTokenSupply
 		impl<T: Config> Drop for NegativeImbalance<T> {
 			fn drop(&mut self) {
-				super::TotalIssuance::<T>::mutate(|key| {
-					if let Some(total) = *key {
-						total.saturating_sub(self.0);
-						total
-					} else {
-						T::Balance::zero()
-					}
+				#[cfg(all(debug_assertions, feature = "strict-imbalances"))]
+				assert!(
+					self.0.is_zero(),
+					"Nonzero NegativeImbalance dropped without being handled"
+				);
+				if self.0.is_zero() {
+					return
+				}
+				let total = super::TotalIssuance::<T>::mutate(|total| {
+					*total = total.saturating_sub(self.0);
+					*total
 				});
+				super::Pallet::<T>::deposit_event(super::Event::Rescinded(self.0, total));
 			}
 		}
 
@@ -260,9 +634,12 @@ pub mod pallet {
 				mem::forget(other);
 			}
 			fn offset(self, other: Self::Opposite) -> SameOrOther<Self, Self::Opposite> {
-				match self.0.cmp(&other.0) {
-					Ordering::Less => SameOrOther::Other(Self::Opposite::new(other.0 - self.0)),
-					Ordering::Greater => SameOrOther::Same(Self::new(self.0 - other.0)),
+				let (ours, theirs) = (self.0, other.0);
+				// Both sides cancel out here, so neither of them may touch TotalIssuance on drop
+				mem::forget((self, other));
+				match ours.cmp(&theirs) {
+					Ordering::Less => SameOrOther::Other(Self::Opposite::new(theirs - ours)),
+					Ordering::Greater => SameOrOther::Same(Self::new(ours - theirs)),
 					Ordering::Equal => SameOrOther::None,
 				}
 			}
@@ -299,9 +676,12 @@ pub mod pallet {
 				mem::forget(other);
 			}
 			fn offset(self, other: Self::Opposite) -> SameOrOther<Self, Self::Opposite> {
-				match self.0.cmp(&other.0) {
-					Ordering::Less => SameOrOther::Other(Self::Opposite::new(other.0 - self.0)),
-					Ordering::Greater => SameOrOther::Same(Self::new(self.0 - other.0)),
+				let (ours, theirs) = (self.0, other.0);
+				// Both sides cancel out here, so neither of them may touch TotalIssuance on drop
+				mem::forget((self, other));
+				match ours.cmp(&theirs) {
+					Ordering::Less => SameOrOther::Other(Self::Opposite::new(theirs - ours)),
+					Ordering::Greater => SameOrOther::Same(Self::new(ours - theirs)),
 					Ordering::Equal => SameOrOther::None,
 				}
 			}
@@ -311,70 +691,1225 @@ pub mod pallet {
 		}
 	} // mod imbalance
 
-	// Finally we are ready to implement Currenct<T::AccountId> for our pallet
+	// Finally we are ready to implement Currency<T::AccountId> for our pallet
 	pub use self::imbalance::{NegativeImbalance, PositiveImbalance};
-	// impl<T: Config> Currency<T::AccountId> for Pallet<T> {
-	// 	type Balance = <T as Config>::Balance;
-	// 	type PositiveImbalance = PositiveImbalance<T>;
-	// 	type NegativeImbalance = NegativeImbalance<T>;
-
-	// 	fn total_balance(who: &T::AccountId) -> Self::Balance {
-	// 		if let Some(account_data) = AccountStore::<T>::get(who) {
-	// 			account_data.total()
-	// 		} else {
-	// 			Self::Balance::zero()
-	// 		}
-	// 	}
-	// 	fn can_slash(who: &T::AccountId, value: Self::Balance) -> bool {
-	// 		if value.is_zero() {
-	// 			return true;
-	// 		}
-	// 		Self::total_balance(who) >= value
-	// 	}
-	// 	fn total_issuance() -> Self::Balance {
-	// 		TotalIssuance::<T>::get().unwrap_or_default()
-	// 	}
-	// 	fn minimum_balance() -> Self::Balance {
-	// 		T::ExistentialDeposit::get()
-	// 	}
-	// 	// Burn `amount` in TotalIssuance storage and return a PositiveImbalance w/o specifying what to do with it
-	// 	fn burn(mut amount: Self::Balance) -> Self::PositiveImbalance {
-	// 		if amount.is_zero() {
-	// 			return PositiveImbalance::zero();
-	// 		}
-	// 		let mut imbalance = amount;
-	// 		<TotalIssuance<T>>::mutate(|&mut total| {
-	// 			if let Some(t) = total {
-	// 				t.checked_sub(&amount).unwrap_or_else(|| {
-	// 					imbalance = t;
-	// 					Zero::zero()
-	// 				})
-	// 			} else {
-	// 				Zero::zero()
-	// 			}
-	// 		});
-	// 		PositiveImbalance::new(imbalance)
-	// 	}
-	// 	// Same as burn but in reverse
-	// 	fn issue(mut amount: Self::Balance) -> Self::NegativeImbalance {
-	// 		if amount.is_zero() {
-	// 			return NegativeImbalance::zero();
-	// 		}
-	// 		let mut imbalance = amount;
-	// 		<TotalIssuance<T>>::mutate(|&mut total| {
-	// 			if let Some(t) = total {
-	// 				t.checked_add(&amount).unwrap_or_else(|| {
-	// 					T::MaxTokenSupply::get()
-	// 				})
-	// 			} else {
-	// 				amount
-	// 			}
-	// 		});
-	// 		NegativeImbalance::new(imbalance)
-	// 	}
-	// 	fn free_balance(who: &T::AccountId) -> Self::Balance {
-	// 		let account_data = Self::account_of(who).unwrap_or_default();
-	// 		account_data.free()
-	// 	}
-	// } // End of Currency impl
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Transfer `schedule.locked` to `target` and keep it locked there, releasing it
+		/// linearly as described by `schedule`
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(4))]
+		pub fn vested_transfer(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<T::Balance, T::BlockNumber>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			ensure!(!schedule.per_block.is_zero(), Error::<T>::InvalidVestingSchedule);
+			ensure!(!Vesting::<T>::contains_key(&target), Error::<T>::ExistingVestingSchedule);
+			<Self as Currency<T::AccountId>>::transfer(
+				&who,
+				&target,
+				schedule.locked,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let still_locked = schedule.locked_at(now);
+			if still_locked.is_zero() {
+				return Ok(())
+			}
+			Self::set_lock(VESTING_ID, &target, still_locked, WithdrawReasons::all());
+			Vesting::<T>::insert(&target, schedule);
+			Ok(())
+		}
+
+		/// Set the free balance of `who` to `new_free`, minting or burning the difference
+		/// Like `make_free_balance_be`, a balance below the existential deposit is reaped
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+		pub fn force_set_balance(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] new_free: T::Balance,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			let account = Self::account_of(&who).unwrap_or_default();
+			let new_free = if new_free.saturating_add(account.locked) < T::ExistentialDeposit::get()
+			{
+				Zero::zero()
+			} else {
+				new_free
+			};
+			if new_free > account.free {
+				let new_issuance = Self::total_issuance()
+					.checked_add(&(new_free - account.free))
+					.ok_or(Error::<T>::Overflow)?;
+				ensure!(
+					new_issuance <= T::MaxTokenSupply::get(),
+					Error::<T>::MaxTokenSupplyExceeded
+				);
+				TotalIssuance::<T>::put(new_issuance);
+			} else {
+				let burned = account.free - new_free;
+				TotalIssuance::<T>::mutate(|total| *total = total.saturating_sub(burned));
+			}
+			Self::mutate_account(&who, |account, _| account.free = new_free);
+			Self::deposit_event(Event::BalanceSet { who, free: new_free });
+			Ok(())
+		}
+
+		/// Reserve `amount` of the signer's funds against `counterparty` until the admin origin
+		/// resolves the dispute with `resolve_dispute`
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 4))]
+		pub fn open_dispute(
+			origin: OriginFor<T>,
+			counterparty: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResult {
+			let opener = ensure_signed(origin)?;
+			let counterparty = T::Lookup::lookup(counterparty)?;
+			ensure!(opener != counterparty, Error::<T>::DisputeWithSelf);
+			let id = Self::next_dispute_id();
+			let next_id = id.checked_add(1).ok_or(Error::<T>::Overflow)?;
+			<Self as NamedReservableCurrency<_>>::reserve_named(
+				&T::DisputeReserveId::get(),
+				&opener,
+				amount,
+			)?;
+			NextDisputeId::<T>::put(next_id);
+			Disputes::<T>::insert(
+				id,
+				Dispute { opener: opener.clone(), counterparty: counterparty.clone(), amount },
+			);
+			Self::deposit_event(Event::DisputeOpened { id, opener, counterparty, amount });
+			Ok(())
+		}
+
+		/// Award the funds reserved for dispute `id` to `winner`, one of its two parties
+		/// If the opener wins its funds are simply unreserved. Otherwise they are slashed from
+		/// the opener's reserve and the slashed imbalance is deposited into the counterparty's
+		/// account, leaving `TotalIssuance` as is. A deposit the counterparty can't take, e.g.
+		/// less than `ExistentialDeposit` into a new account, is burned instead
+		/// If part of the reserve was slashed elsewhere in the meantime, only what is left of
+		/// it is awarded
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 5))]
+		pub fn resolve_dispute(
+			origin: OriginFor<T>,
+			id: u32,
+			winner: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let winner = T::Lookup::lookup(winner)?;
+			let dispute = Self::dispute(id).ok_or(Error::<T>::UnknownDispute)?;
+			ensure!(
+				winner == dispute.opener || winner == dispute.counterparty,
+				Error::<T>::NotDisputeParty
+			);
+			let reserve_id = T::DisputeReserveId::get();
+			let awarded = if winner == dispute.opener {
+				let missing = <Self as NamedReservableCurrency<_>>::unreserve_named(
+					&reserve_id,
+					&dispute.opener,
+					dispute.amount,
+				);
+				dispute.amount - missing
+			} else {
+				let (slashed, _) = <Self as NamedReservableCurrency<_>>::slash_reserved_named(
+					&reserve_id,
+					&dispute.opener,
+					dispute.amount,
+				);
+				let awarded = slashed.peek();
+				<Self as Currency<_>>::resolve_creating(&winner, slashed);
+				awarded
+			};
+			Disputes::<T>::remove(id);
+			Self::deposit_event(Event::DisputeResolved { id, winner, awarded });
+			Ok(())
+		}
+
+		/// Emit the current balance breakdown of `who` as a `BalanceReport` event, for light
+		/// clients that follow events rather than query state
+		/// Writes nothing, it is only a notification, and anyone may call it
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(2))]
+		pub fn report_balance(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			let account = Self::account_data(&who);
+			Self::deposit_event(Event::BalanceReport {
+				usable: Self::usable_balance(&who),
+				free: account.free(),
+				locked: account.locked(),
+				who,
+			});
+			Ok(())
+		}
+
+		/// Release whatever part of the signer's vesting schedule has vested so far
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+		pub fn vest(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let schedule = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			let still_locked = schedule.locked_at(frame_system::Pallet::<T>::block_number());
+			if still_locked.is_zero() {
+				Self::remove_lock(VESTING_ID, &who);
+				Vesting::<T>::remove(&who);
+				Self::deposit_event(Event::VestingCompleted(who));
+			} else {
+				Self::set_lock(VESTING_ID, &who, still_locked, WithdrawReasons::all());
+				Self::deposit_event(Event::VestingUpdated(who, still_locked));
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Free balance of `who`, zero if the account doesn't exist
+		pub fn free_balance(who: &T::AccountId) -> T::Balance {
+			Self::account_of(who).unwrap_or_default().free()
+		}
+
+		/// Free and locked balance of `who` together, the default if the account doesn't exist
+		pub fn account_data(who: &T::AccountId) -> AccountData<T::Balance> {
+			Self::account_of(who).unwrap_or_default()
+		}
+
+		/// Whether `who` holds any funds, free or reserved
+		/// Accounts are removed from storage once both reach zero, or once they are reaped
+		pub fn account_exists(who: &T::AccountId) -> bool {
+			AccountStore::<T>::contains_key(who)
+		}
+
+		/// Reserved balance of `who`, zero if the account doesn't exist
+		pub fn reserved_balance(who: &T::AccountId) -> T::Balance {
+			Self::account_of(who).unwrap_or_default().locked()
+		}
+
+		/// Part of the free balance of `who` not held by any balance lock
+		pub fn usable_balance(who: &T::AccountId) -> T::Balance {
+			Self::compute_reducible(who, false)
+		}
+
+		/// Active balance locks of `who` as (id, amount) pairs
+		pub fn lock_summary(who: &T::AccountId) -> Vec<(LockIdentifier, T::Balance)> {
+			Self::locks(who).iter().map(|lock| (lock.id, lock.amount)).collect()
+		}
+
+		/// Most `who` can actually spend: the usable balance, minus the existential deposit
+		/// when the account has to be kept alive
+		pub fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> T::Balance {
+			Self::compute_reducible(who, keep_alive)
+		}
+
+		/// Single source of what `who` can spend: its free balance minus the largest lock, and
+		/// minus the existential deposit with `keep_alive`. The getters above, `settle` and
+		/// `fungible::Inspect` all go through it so they can't disagree
+		fn compute_reducible(who: &T::AccountId, keep_alive: bool) -> T::Balance {
+			let largest_lock =
+				Self::locks(who).iter().map(|lock| lock.amount).max().unwrap_or_else(Zero::zero);
+			let usable = Self::free_balance(who).saturating_sub(largest_lock);
+			if keep_alive {
+				usable.saturating_sub(T::ExistentialDeposit::get())
+			} else {
+				usable
+			}
+		}
+
+		/// Credit `who` with the funds behind `imbalance`
+		/// Whatever couldn't be credited, e.g. because it is below `ExistentialDeposit` for a
+		/// new account, is handed back for the caller to deal with, typically via `try_drop`
+		/// The funds are already part of `TotalIssuance`, so `MaxTokenSupply` doesn't limit them
+		pub fn settle_into(
+			who: &T::AccountId,
+			imbalance: NegativeImbalance<T>,
+		) -> Result<(), NegativeImbalance<T>> {
+			let amount = imbalance.peek();
+			if amount.is_zero() {
+				return Ok(())
+			}
+			let credited = match Self::deposit_free(who, amount, false) {
+				Ok(credited) => credited,
+				Err(_) => return Err(imbalance),
+			};
+			Self::deposit_event(Event::Deposit { who: who.clone(), amount });
+			match imbalance.offset(credited) {
+				SameOrOther::Same(leftover) => Err(leftover),
+				// Never credits more than the imbalance, so nothing is left on the other side
+				SameOrOther::None | SameOrOther::Other(_) => Ok(()),
+			}
+		}
+
+		/// Apply `imbalance` to the free balance of `who` instead of dropping it
+		/// A positive imbalance is deposited and a negative one withdrawn, as far as locks
+		/// allow, and `TotalIssuance` moves along with the balance exactly as the imbalance's
+		/// `Drop` would have moved it. Whatever couldn't be applied is handed back
+		pub fn settle(
+			who: &T::AccountId,
+			imbalance: SignedImbalance<T::Balance, PositiveImbalance<T>>,
+		) -> Result<(), SignedImbalance<T::Balance, PositiveImbalance<T>>> {
+			match imbalance {
+				SignedImbalance::Positive(imbalance) => {
+					let amount = imbalance.peek();
+					// Settling grows the supply, which must stay within `MaxTokenSupply`
+					if amount > Self::issuance_headroom() {
+						return Err(SignedImbalance::Positive(imbalance))
+					}
+					let deposited = Self::try_mutate_account(
+						who,
+						|account, is_new| -> Result<(), DispatchError> {
+							ensure!(
+								!is_new || amount >= T::ExistentialDeposit::get(),
+								Error::<T>::ExistentialDeposit
+							);
+							account.free =
+								account.free.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+							Ok(())
+						},
+					);
+					if deposited.is_err() {
+						return Err(SignedImbalance::Positive(imbalance))
+					}
+					let total = TotalIssuance::<T>::mutate(|total| {
+						*total = total.saturating_add(amount);
+						*total
+					});
+					mem::forget(imbalance);
+					if !amount.is_zero() {
+						Self::deposit_event(Event::Issued(amount, total));
+					}
+					Ok(())
+				},
+				SignedImbalance::Negative(imbalance) => {
+					let withdrawn = Self::compute_reducible(who, false).min(imbalance.peek());
+					Self::mutate_account(who, |account, _| account.free -= withdrawn);
+					let total = TotalIssuance::<T>::mutate(|total| {
+						*total = total.saturating_sub(withdrawn);
+						*total
+					});
+					if !withdrawn.is_zero() {
+						Self::deposit_event(Event::Rescinded(withdrawn, total));
+					}
+					let (settled, leftover) = imbalance.split(withdrawn);
+					mem::forget(settled);
+					leftover.drop_zero().map_err(SignedImbalance::Negative)
+				},
+			}
+		}
+
+		/// Hold `amount` of `who`'s free balance as a deposit for some on-chain action
+		/// Fails with `InsufficientBalance` when the free balance can't cover it, or
+		/// `LiquidityRestrictions` when a balance lock holds the funds
+		pub fn collect_deposit(who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			<Self as ReservableCurrency<T::AccountId>>::reserve(who, amount)
+		}
+
+		/// Give back up to `amount` of a deposit taken with `collect_deposit`
+		/// Returns the part that couldn't be refunded because less than that was held
+		pub fn refund_deposit(who: &T::AccountId, amount: T::Balance) -> T::Balance {
+			<Self as ReservableCurrency<T::AccountId>>::unreserve(who, amount)
+		}
+
+		/// Mint `value` into `who`, handing the resulting imbalance to `MintHandler`
+		/// Like `issue`, only what still fits under `MaxTokenSupply` is minted. The mint is
+		/// reported once, by `Issued` when the imbalance grows the supply
+		pub fn mint_into(who: &T::AccountId, value: T::Balance) {
+			let value = value.min(Self::issuance_headroom());
+			if value.is_zero() {
+				return
+			}
+			let imbalance =
+				Self::deposit_free(who, value, false).unwrap_or_else(|_| PositiveImbalance::zero());
+			T::MintHandler::on_unbalanced(imbalance);
+		}
+
+		/// Slash up to `value` from `who`, handing the slashed funds to `SlashHandler`
+		/// Returns the amount that couldn't be slashed
+		pub fn slash_into_handler(who: &T::AccountId, value: T::Balance) -> T::Balance {
+			let (imbalance, shortfall) = <Self as Currency<T::AccountId>>::slash(who, value);
+			T::SlashHandler::on_unbalanced(imbalance);
+			shortfall
+		}
+
+		/// Pay `reward` out of `slashed` to `reporter` and hand the rest to `SlashHandler`,
+		/// e.g. for staking pallets rewarding whoever reported an offence
+		/// Whatever part of the reward couldn't be credited to the reporter is handed back
+		pub fn reward_from_slash(
+			reporter: &T::AccountId,
+			slashed: NegativeImbalance<T>,
+			reward: T::Balance,
+		) -> Result<(), NegativeImbalance<T>> {
+			let (reward, rest) = slashed.split(reward);
+			T::SlashHandler::on_unbalanced(rest);
+			Self::settle_into(reporter, reward)
+		}
+
+		/// Widen `b` to a `u128`, `None` if it doesn't fit
+		pub fn balance_to_u128(b: T::Balance) -> Option<u128> {
+			b.try_into().ok()
+		}
+
+		/// Narrow `v` to a `Balance`, `None` if it doesn't fit rather than saturating
+		pub fn u128_to_balance(v: u128) -> Option<T::Balance> {
+			v.try_into().ok()
+		}
+	}
+
+	// Private Helper functions
+	impl<T: Config> Pallet<T> {
+		/// Hand `dust` reaped from `who` to `DustTrap`, or burn it out of `TotalIssuance`
+		/// when there's no trap or the trap can't hold any more
+		fn handle_dust(who: &T::AccountId, dust: T::Balance) {
+			if let Some(trap) = T::DustTrap::get() {
+				let credited = Self::try_mutate_account(&trap, |account, _| -> DispatchResult {
+					account.free = account.free.checked_add(&dust).ok_or(Error::<T>::Overflow)?;
+					Ok(())
+				});
+				if credited.is_ok() {
+					Self::deposit_event(Event::Transfer(who.clone(), trap, dust));
+					return
+				}
+			}
+			TotalIssuance::<T>::mutate(|total| *total = total.saturating_sub(dust));
+			Self::deposit_event(Event::DustLost(who.clone(), dust));
+		}
+
+		/// v0 -> v1: `TotalIssuance` became a `ValueQuery` with the same encoding, so only the
+		/// version needs recording
+		fn migrate_to_v1() -> Weight {
+			if Self::on_chain_storage_version() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+			STORAGE_VERSION.put::<Self>();
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		/// How much more can be issued before `TotalIssuance` hits `MaxTokenSupply`
+		fn issuance_headroom() -> T::Balance {
+			T::MaxTokenSupply::get().saturating_sub(Self::total_issuance())
+		}
+
+		/// Add `value` to the free balance of `who` without emitting any event, returning the
+		/// imbalance `TotalIssuance` has to be settled with. A new account must be created
+		/// with at least the existential deposit, and only when `existing_only` isn't set
+		fn deposit_free(
+			who: &T::AccountId,
+			value: T::Balance,
+			existing_only: bool,
+		) -> Result<PositiveImbalance<T>, DispatchError> {
+			Self::try_mutate_account(who, |account, is_new| -> Result<_, DispatchError> {
+				ensure!(!existing_only || !is_new, Error::<T>::DeadAccount);
+				ensure!(
+					!is_new || value >= T::ExistentialDeposit::get(),
+					Error::<T>::ExistentialDeposit
+				);
+				account.free = account.free.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+				Ok(PositiveImbalance::new(value))
+			})
+		}
+
+		/// Mutate an account, creating it if it doesn't exist yet
+		/// The closure is told whether the account is new, and the account is removed
+		/// from storage once it holds no funds at all
+		fn try_mutate_account<R, E>(
+			who: &T::AccountId,
+			f: impl FnOnce(&mut AccountData<T::Balance>, bool) -> Result<R, E>,
+		) -> Result<R, E> {
+			AccountStore::<T>::try_mutate_exists(who, |maybe_account| {
+				let is_new = maybe_account.is_none();
+				let mut account = maybe_account.take().unwrap_or_default();
+				f(&mut account, is_new).map(move |result| {
+					*maybe_account = if account.total().is_zero() { None } else { Some(account) };
+					result
+				})
+			})
+		}
+
+		fn update_locks(who: &T::AccountId, locks: Vec<BalanceLock<T::Balance>>) {
+			if locks.is_empty() {
+				Locks::<T>::remove(who);
+			} else {
+				// Going over `MaxLocks` is tolerated rather than silently dropping a lock
+				let bounded_locks = WeakBoundedVec::<_, T::MaxLocks>::force_from(
+					locks,
+					Some("Kryptokurrency Update Locks"),
+				);
+				Locks::<T>::insert(who, bounded_locks);
+			}
+		}
+
+		fn mutate_account<R>(
+			who: &T::AccountId,
+			f: impl FnOnce(&mut AccountData<T::Balance>, bool) -> R,
+		) -> R {
+			Self::try_mutate_account(who, |account, is_new| -> Result<R, Infallible> {
+				Ok(f(account, is_new))
+			})
+			.unwrap_or_else(|never| match never {})
+		}
+	}
+
+	impl<T: Config> Currency<T::AccountId> for Pallet<T> {
+		type Balance = T::Balance;
+		type PositiveImbalance = PositiveImbalance<T>;
+		type NegativeImbalance = NegativeImbalance<T>;
+
+		fn total_balance(who: &T::AccountId) -> Self::Balance {
+			Self::account_of(who).unwrap_or_default().total()
+		}
+
+		fn can_slash(who: &T::AccountId, value: Self::Balance) -> bool {
+			if value.is_zero() {
+				return true
+			}
+			Self::free_balance(who) >= value
+		}
+
+		fn total_issuance() -> Self::Balance {
+			TotalIssuance::<T>::get()
+		}
+
+		fn minimum_balance() -> Self::Balance {
+			T::ExistentialDeposit::get()
+		}
+
+		// Reduce TotalIssuance by `amount` and return a PositiveImbalance which, unless
+		// offset by removing funds from some account, restores TotalIssuance when dropped
+		fn burn(mut amount: Self::Balance) -> Self::PositiveImbalance {
+			if amount.is_zero() {
+				return PositiveImbalance::zero()
+			}
+			TotalIssuance::<T>::mutate(|issued| {
+				*issued = issued.checked_sub(&amount).unwrap_or_else(|| {
+					amount = *issued;
+					Zero::zero()
+				});
+			});
+			PositiveImbalance::new(amount)
+		}
+
+		// Same as burn but in reverse, never issuing past MaxTokenSupply
+		fn issue(mut amount: Self::Balance) -> Self::NegativeImbalance {
+			if amount.is_zero() {
+				return NegativeImbalance::zero()
+			}
+			TotalIssuance::<T>::mutate(|issued| {
+				amount = amount.min(T::MaxTokenSupply::get().saturating_sub(*issued));
+				*issued = issued.saturating_add(amount);
+			});
+			NegativeImbalance::new(amount)
+		}
+
+		fn free_balance(who: &T::AccountId) -> Self::Balance {
+			Self::account_of(who).unwrap_or_default().free()
+		}
+
+		// The new free balance must still cover every lock that applies to this withdrawal
+		fn ensure_can_withdraw(
+			who: &T::AccountId,
+			amount: Self::Balance,
+			reasons: WithdrawReasons,
+			new_balance: Self::Balance,
+		) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(())
+			}
+			let min_balance = Self::locks(who)
+				.iter()
+				.filter(|lock| lock.reasons.applies_to(reasons))
+				.map(|lock| lock.amount)
+				.max()
+				.unwrap_or_else(Zero::zero);
+			ensure!(new_balance >= min_balance, Error::<T>::LiquidityRestrictions);
+			Ok(())
+		}
+
+		fn transfer(
+			transactor: &T::AccountId,
+			dest: &T::AccountId,
+			value: Self::Balance,
+			existence_requirement: ExistenceRequirement,
+		) -> DispatchResult {
+			if value.is_zero() || transactor == dest {
+				return Ok(())
+			}
+			let dust = Self::try_mutate_account(
+				dest,
+				|to_account, is_new| -> Result<T::Balance, DispatchError> {
+					ensure!(
+						!is_new || value >= T::ExistentialDeposit::get(),
+						Error::<T>::ExistentialDeposit
+					);
+					Self::try_mutate_account(
+						transactor,
+						|from_account, _| -> Result<T::Balance, DispatchError> {
+							from_account.free = from_account
+								.free
+								.checked_sub(&value)
+								.ok_or(Error::<T>::InsufficientBalance)?;
+							to_account.free =
+								to_account.free.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+							Self::ensure_can_withdraw(
+								transactor,
+								value,
+								WithdrawReasons::TRANSFER,
+								from_account.free,
+							)?;
+							// Dropping below the existential deposit reaps the sender, which only
+							// `AllowDeath` permits. Reserved funds keep the account around,
+							// otherwise whatever dust is left goes along with it
+							let new_total = from_account.total();
+							if new_total.is_zero() || new_total < T::ExistentialDeposit::get() {
+								ensure!(
+									existence_requirement == ExistenceRequirement::AllowDeath,
+									Error::<T>::KeepAlive
+								);
+								if from_account.locked.is_zero() {
+									return Ok(mem::take(&mut from_account.free))
+								}
+							}
+							Ok(Zero::zero())
+						},
+					)
+				},
+			)?;
+			if !dust.is_zero() {
+				Self::handle_dust(transactor, dust);
+			}
+			Ok(())
+		}
+
+		fn slash(
+			who: &T::AccountId,
+			value: Self::Balance,
+		) -> (Self::NegativeImbalance, Self::Balance) {
+			if value.is_zero() {
+				return (NegativeImbalance::zero(), Zero::zero())
+			}
+			if Self::total_balance(who).is_zero() {
+				return (NegativeImbalance::zero(), value)
+			}
+			// Take from free funds first and only dip into reserved funds for the rest
+			let (imbalance, shortfall) = Self::mutate_account(who, |account, _| {
+				let free_slash = account.free.min(value);
+				account.free -= free_slash;
+				let reserved_slash = account.locked.min(value - free_slash);
+				account.locked -= reserved_slash;
+				let slashed = free_slash + reserved_slash;
+				(NegativeImbalance::new(slashed), value - slashed)
+			});
+			Self::deposit_event(Event::Slashed(who.clone(), imbalance.peek()));
+			(imbalance, shortfall)
+		}
+
+		// Only credits accounts that already exist, failing with `DeadAccount` otherwise
+		// The deposit is new money, so it fails with `MaxTokenSupplyExceeded` rather than
+		// take `TotalIssuance` over `MaxTokenSupply`
+		fn deposit_into_existing(
+			who: &T::AccountId,
+			value: Self::Balance,
+		) -> Result<Self::PositiveImbalance, DispatchError> {
+			if value.is_zero() {
+				return Ok(PositiveImbalance::zero())
+			}
+			ensure!(value <= Self::issuance_headroom(), Error::<T>::MaxTokenSupplyExceeded);
+			let imbalance = Self::deposit_free(who, value, true)?;
+			Self::deposit_event(Event::Deposit { who: who.clone(), amount: value });
+			Ok(imbalance)
+		}
+
+		// A new account must be created with at least the existential deposit, otherwise
+		// nothing is deposited and a zero imbalance is returned
+		// Like `issue`, only what still fits under `MaxTokenSupply` is deposited
+		fn deposit_creating(who: &T::AccountId, value: Self::Balance) -> Self::PositiveImbalance {
+			let value = value.min(Self::issuance_headroom());
+			if value.is_zero() {
+				return PositiveImbalance::zero()
+			}
+			let imbalance =
+				Self::deposit_free(who, value, false).unwrap_or_else(|_| PositiveImbalance::zero());
+			if !imbalance.peek().is_zero() {
+				Self::deposit_event(Event::Deposit { who: who.clone(), amount: value });
+			}
+			imbalance
+		}
+
+		// Unlike a deposit, the funds being resolved are already part of `TotalIssuance`, so
+		// `MaxTokenSupply` doesn't limit them
+		fn resolve_into_existing(
+			who: &T::AccountId,
+			value: Self::NegativeImbalance,
+		) -> Result<(), Self::NegativeImbalance> {
+			let amount = value.peek();
+			if amount.is_zero() {
+				return Ok(())
+			}
+			match Self::deposit_free(who, amount, true) {
+				Ok(deposited) => {
+					Self::deposit_event(Event::Deposit { who: who.clone(), amount });
+					drop(value.offset(deposited));
+					Ok(())
+				},
+				Err(_) => Err(value),
+			}
+		}
+
+		// Same as `resolve_into_existing`, creating the account if it needs to
+		// Whatever couldn't be credited is dropped, burning it
+		fn resolve_creating(who: &T::AccountId, value: Self::NegativeImbalance) {
+			if let Err(leftover) = Self::settle_into(who, value) {
+				drop(leftover);
+			}
+		}
+
+		fn withdraw(
+			who: &T::AccountId,
+			value: Self::Balance,
+			reasons: WithdrawReasons,
+			liveness: ExistenceRequirement,
+		) -> Result<Self::NegativeImbalance, DispatchError> {
+			if value.is_zero() {
+				return Ok(NegativeImbalance::zero())
+			}
+			Self::try_mutate_account(who, |account, _| -> Result<_, DispatchError> {
+				let new_free =
+					account.free.checked_sub(&value).ok_or(Error::<T>::InsufficientBalance)?;
+				// Emptying the account reaps it, which is only fine with `AllowDeath`, and it may
+				// never be left holding less than the existential deposit
+				let new_total = new_free.saturating_add(account.locked);
+				if new_total.is_zero() || new_total < T::ExistentialDeposit::get() {
+					ensure!(liveness == ExistenceRequirement::AllowDeath, Error::<T>::KeepAlive);
+					ensure!(new_total.is_zero(), Error::<T>::ExistentialDeposit);
+				}
+				Self::ensure_can_withdraw(who, value, reasons, new_free)?;
+				account.free = new_free;
+				Ok(NegativeImbalance::new(value))
+			})
+			.map(|imbalance| {
+				Self::deposit_event(Event::Withdraw { who: who.clone(), amount: value });
+				imbalance
+			})
+		}
+
+		fn make_free_balance_be(
+			who: &T::AccountId,
+			value: Self::Balance,
+		) -> SignedImbalance<Self::Balance, Self::PositiveImbalance> {
+			Self::mutate_account(who, |account, is_new| {
+				// Raising the balance mints the difference, which is kept within `MaxTokenSupply`
+				let value = value.min(account.free.saturating_add(Self::issuance_headroom()));
+				// A balance below the existential deposit can't be kept, so the free balance is
+				// reaped instead. This also means a new account is simply never created
+				let value = if value.saturating_add(account.locked) < T::ExistentialDeposit::get() {
+					Zero::zero()
+				} else {
+					value
+				};
+				if is_new && value.is_zero() {
+					return SignedImbalance::Positive(PositiveImbalance::zero())
+				}
+				let imbalance = if account.free <= value {
+					SignedImbalance::Positive(PositiveImbalance::new(value - account.free))
+				} else {
+					SignedImbalance::Negative(NegativeImbalance::new(account.free - value))
+				};
+				account.free = value;
+				imbalance
+			})
+		}
+	} // End of Currency impl
+
+	/// Reserved funds live in the `locked` field of `AccountData`
+	impl<T: Config> ReservableCurrency<T::AccountId> for Pallet<T> {
+		fn can_reserve(who: &T::AccountId, value: Self::Balance) -> bool {
+			if value.is_zero() {
+				return true
+			}
+			Self::account_of(who).map_or(false, |account| {
+				account.free.checked_sub(&value).map_or(false, |new_free| {
+					Self::ensure_can_withdraw(who, value, WithdrawReasons::RESERVE, new_free)
+						.is_ok()
+				})
+			})
+		}
+
+		fn reserved_balance(who: &T::AccountId) -> Self::Balance {
+			Self::account_of(who).unwrap_or_default().locked()
+		}
+
+		fn reserve(who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+			if value.is_zero() {
+				return Ok(())
+			}
+			Self::try_mutate_account(who, |account, _| -> DispatchResult {
+				account.free =
+					account.free.checked_sub(&value).ok_or(Error::<T>::InsufficientBalance)?;
+				account.locked = account.locked.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+				Self::ensure_can_withdraw(who, value, WithdrawReasons::RESERVE, account.free)
+			})
+		}
+
+		fn unreserve(who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+			if value.is_zero() {
+				return Zero::zero()
+			}
+			if Self::total_balance(who).is_zero() {
+				return value
+			}
+			Self::mutate_account(who, |account, _| {
+				let actual = account.locked.min(value);
+				account.locked -= actual;
+				account.free = account.free.saturating_add(actual);
+				value - actual
+			})
+		}
+
+		fn slash_reserved(
+			who: &T::AccountId,
+			value: Self::Balance,
+		) -> (Self::NegativeImbalance, Self::Balance) {
+			if value.is_zero() {
+				return (NegativeImbalance::zero(), Zero::zero())
+			}
+			if Self::total_balance(who).is_zero() {
+				return (NegativeImbalance::zero(), value)
+			}
+			let (imbalance, shortfall) = Self::mutate_account(who, |account, _| {
+				let actual = account.locked.min(value);
+				account.locked -= actual;
+				(NegativeImbalance::new(actual), value - actual)
+			});
+			Self::deposit_event(Event::Slashed(who.clone(), imbalance.peek()));
+			(imbalance, shortfall)
+		}
+
+		fn repatriate_reserved(
+			slashed: &T::AccountId,
+			beneficiary: &T::AccountId,
+			value: Self::Balance,
+			status: BalanceStatus,
+		) -> Result<Self::Balance, DispatchError> {
+			if value.is_zero() {
+				return Ok(Zero::zero())
+			}
+			if slashed == beneficiary {
+				return match status {
+					BalanceStatus::Free => Ok(Self::unreserve(slashed, value)),
+					BalanceStatus::Reserved =>
+						Ok(value.saturating_sub(Self::reserved_balance(slashed))),
+				}
+			}
+			Self::try_mutate_account(
+				beneficiary,
+				|to_account, is_new| -> Result<_, DispatchError> {
+					ensure!(!is_new, Error::<T>::DeadAccount);
+					Self::try_mutate_account(
+						slashed,
+						|from_account, _| -> Result<_, DispatchError> {
+							let actual = from_account.locked.min(value);
+							match status {
+								BalanceStatus::Free =>
+									to_account.free = to_account
+										.free
+										.checked_add(&actual)
+										.ok_or(Error::<T>::Overflow)?,
+								BalanceStatus::Reserved =>
+									to_account.locked = to_account
+										.locked
+										.checked_add(&actual)
+										.ok_or(Error::<T>::Overflow)?,
+							}
+							from_account.locked -= actual;
+							Ok(value - actual)
+						},
+					)
+				},
+			)
+		}
+	} // End of ReservableCurrency impl
+
+	impl<T: Config> LockableCurrency<T::AccountId> for Pallet<T> {
+		type Moment = T::BlockNumber;
+		type MaxLocks = T::MaxLocks;
+
+		// Set or replace the lock with this id
+		fn set_lock(
+			id: LockIdentifier,
+			who: &T::AccountId,
+			amount: Self::Balance,
+			reasons: WithdrawReasons,
+		) {
+			if amount.is_zero() || reasons.is_empty() {
+				return Self::remove_lock(id, who)
+			}
+			let mut new_lock = Some(BalanceLock { id, amount, reasons: reasons.into() });
+			let mut locks = Self::locks(who)
+				.into_inner()
+				.into_iter()
+				.filter_map(|lock| if lock.id == id { new_lock.take() } else { Some(lock) })
+				.collect::<Vec<_>>();
+			if let Some(lock) = new_lock {
+				locks.push(lock)
+			}
+			Self::update_locks(who, locks);
+		}
+
+		// Extend the lock with this id to at least `amount` and the union of both reasons
+		fn extend_lock(
+			id: LockIdentifier,
+			who: &T::AccountId,
+			amount: Self::Balance,
+			reasons: WithdrawReasons,
+		) {
+			if amount.is_zero() || reasons.is_empty() {
+				return
+			}
+			let mut new_lock = Some(BalanceLock { id, amount, reasons: reasons.into() });
+			let mut locks = Self::locks(who)
+				.into_inner()
+				.into_iter()
+				.filter_map(|lock| {
+					if lock.id == id {
+						new_lock.take().map(|new_lock| BalanceLock {
+							id: lock.id,
+							amount: lock.amount.max(new_lock.amount),
+							reasons: lock.reasons.merge(new_lock.reasons),
+						})
+					} else {
+						Some(lock)
+					}
+				})
+				.collect::<Vec<_>>();
+			if let Some(lock) = new_lock {
+				locks.push(lock)
+			}
+			Self::update_locks(who, locks);
+		}
+
+		fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+			let locks = Self::locks(who)
+				.into_inner()
+				.into_iter()
+				.filter(|lock| lock.id != id)
+				.collect::<Vec<_>>();
+			Self::update_locks(who, locks);
+		}
+	} // End of LockableCurrency impl
+
+	/// Named reserves are tracked in `ReservesOf` on top of the plain reserve, so that
+	/// different subsystems can hold deposits on the same account independently
+	impl<T: Config> NamedReservableCurrency<T::AccountId> for Pallet<T> {
+		type ReserveIdentifier = T::ReserveIdentifier;
+
+		fn reserved_balance_named(
+			id: &Self::ReserveIdentifier,
+			who: &T::AccountId,
+		) -> Self::Balance {
+			let reserves = Self::reserves_of(who);
+			reserves
+				.binary_search_by_key(id, |data| data.id)
+				.map(|index| reserves[index].amount)
+				.unwrap_or_default()
+		}
+
+		fn reserve_named(
+			id: &Self::ReserveIdentifier,
+			who: &T::AccountId,
+			value: Self::Balance,
+		) -> DispatchResult {
+			if value.is_zero() {
+				return Ok(())
+			}
+			ReservesOf::<T>::try_mutate(who, |reserves| -> DispatchResult {
+				match reserves.binary_search_by_key(id, |data| data.id) {
+					Ok(index) => {
+						reserves[index].amount = reserves[index]
+							.amount
+							.checked_add(&value)
+							.ok_or(Error::<T>::Overflow)?;
+					},
+					Err(index) => {
+						reserves
+							.try_insert(index, ReserveData { id: *id, amount: value })
+							.map_err(|_| Error::<T>::TooManyReserves)?;
+					},
+				};
+				<Self as ReservableCurrency<_>>::reserve(who, value)
+			})
+		}
+
+		fn unreserve_named(
+			id: &Self::ReserveIdentifier,
+			who: &T::AccountId,
+			value: Self::Balance,
+		) -> Self::Balance {
+			if value.is_zero() {
+				return Zero::zero()
+			}
+			ReservesOf::<T>::mutate_exists(who, |maybe_reserves| -> Self::Balance {
+				if let Some(reserves) = maybe_reserves.as_mut() {
+					match reserves.binary_search_by_key(id, |data| data.id) {
+						Ok(index) => {
+							let to_change = cmp::min(reserves[index].amount, value);
+							let remain = <Self as ReservableCurrency<_>>::unreserve(who, to_change);
+							// remain should always be zero, but be defensive about it
+							let actual = to_change.saturating_sub(remain);
+							reserves[index].amount -= actual;
+							if reserves[index].amount.is_zero() {
+								if reserves.len() == 1 {
+									// no more named reserves, drop the entry altogether
+									*maybe_reserves = None;
+								} else {
+									reserves.remove(index);
+								}
+							}
+							value - actual
+						},
+						Err(_) => value,
+					}
+				} else {
+					value
+				}
+			})
+		}
+
+		fn slash_reserved_named(
+			id: &Self::ReserveIdentifier,
+			who: &T::AccountId,
+			value: Self::Balance,
+		) -> (Self::NegativeImbalance, Self::Balance) {
+			if value.is_zero() {
+				return (NegativeImbalance::zero(), Zero::zero())
+			}
+			ReservesOf::<T>::mutate(who, |reserves| -> (Self::NegativeImbalance, Self::Balance) {
+				match reserves.binary_search_by_key(id, |data| data.id) {
+					Ok(index) => {
+						let to_change = cmp::min(reserves[index].amount, value);
+						let (imbalance, remain) =
+							<Self as ReservableCurrency<_>>::slash_reserved(who, to_change);
+						// remain should always be zero, but be defensive about it
+						let actual = to_change.saturating_sub(remain);
+						reserves[index].amount -= actual;
+						if reserves[index].amount.is_zero() {
+							reserves.remove(index);
+						}
+						(imbalance, value - actual)
+					},
+					Err(_) => (NegativeImbalance::zero(), value),
+				}
+			})
+		}
+
+		fn repatriate_reserved_named(
+			id: &Self::ReserveIdentifier,
+			slashed: &T::AccountId,
+			beneficiary: &T::AccountId,
+			value: Self::Balance,
+			status: BalanceStatus,
+		) -> Result<Self::Balance, DispatchError> {
+			if value.is_zero() {
+				return Ok(Zero::zero())
+			}
+			if slashed == beneficiary {
+				return match status {
+					BalanceStatus::Free => Ok(Self::unreserve_named(id, slashed, value)),
+					BalanceStatus::Reserved =>
+						Ok(value.saturating_sub(Self::reserved_balance_named(id, slashed))),
+				}
+			}
+			ReservesOf::<T>::try_mutate(slashed, |reserves| -> Result<_, DispatchError> {
+				match reserves.binary_search_by_key(id, |data| data.id) {
+					Ok(index) => {
+						let to_change = cmp::min(reserves[index].amount, value);
+						let actual = if status == BalanceStatus::Reserved {
+							// Keep the repatriated funds under the same id on the beneficiary
+							ReservesOf::<T>::try_mutate(
+								beneficiary,
+								|beneficiary_reserves| -> Result<_, DispatchError> {
+									let remain =
+										<Self as ReservableCurrency<_>>::repatriate_reserved(
+											slashed,
+											beneficiary,
+											to_change,
+											status,
+										)?;
+									let actual = to_change.saturating_sub(remain);
+									match beneficiary_reserves
+										.binary_search_by_key(id, |data| data.id)
+									{
+										Ok(index) => {
+											beneficiary_reserves[index].amount =
+												beneficiary_reserves[index]
+													.amount
+													.saturating_add(actual);
+										},
+										Err(index) => {
+											beneficiary_reserves
+												.try_insert(
+													index,
+													ReserveData { id: *id, amount: actual },
+												)
+												.map_err(|_| Error::<T>::TooManyReserves)?;
+										},
+									}
+									Ok(actual)
+								},
+							)?
+						} else {
+							let remain = <Self as ReservableCurrency<_>>::repatriate_reserved(
+								slashed,
+								beneficiary,
+								to_change,
+								status,
+							)?;
+							to_change.saturating_sub(remain)
+						};
+						reserves[index].amount -= actual;
+						if reserves[index].amount.is_zero() {
+							reserves.remove(index);
+						}
+						Ok(value - actual)
+					},
+					Err(_) => Ok(value),
+				}
+			})
+		}
+	} // End of NamedReservableCurrency impl
+
+	/// `fungible` view of the same balances, for pallets written against the newer traits
+	impl<T: Config> fungible::Inspect<T::AccountId> for Pallet<T> {
+		type Balance = T::Balance;
+
+		fn total_issuance() -> Self::Balance {
+			TotalIssuance::<T>::get()
+		}
+
+		fn minimum_balance() -> Self::Balance {
+			T::ExistentialDeposit::get()
+		}
+
+		fn balance(who: &T::AccountId) -> Self::Balance {
+			Self::account_data(who).total()
+		}
+
+		fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> Self::Balance {
+			Pallet::<T>::compute_reducible(who, keep_alive)
+		}
+
+		fn can_deposit(who: &T::AccountId, amount: Self::Balance) -> DepositConsequence {
+			if amount.is_zero() {
+				return DepositConsequence::Success
+			}
+			if TotalIssuance::<T>::get().checked_add(&amount).is_none() {
+				return DepositConsequence::Overflow
+			}
+			match Self::account_of(who) {
+				None if amount < T::ExistentialDeposit::get() => DepositConsequence::BelowMinimum,
+				account => match account.unwrap_or_default().free.checked_add(&amount) {
+					Some(_) => DepositConsequence::Success,
+					None => DepositConsequence::Overflow,
+				},
+			}
+		}
+
+		fn can_withdraw(
+			who: &T::AccountId,
+			amount: Self::Balance,
+		) -> WithdrawConsequence<Self::Balance> {
+			if amount.is_zero() {
+				return WithdrawConsequence::Success
+			}
+			if TotalIssuance::<T>::get().checked_sub(&amount).is_none() {
+				return WithdrawConsequence::Underflow
+			}
+			let account = Self::account_data(who);
+			let new_free = match account.free.checked_sub(&amount) {
+				Some(new_free) => new_free,
+				None => return WithdrawConsequence::NoFunds,
+			};
+			if Self::ensure_can_withdraw(who, amount, WithdrawReasons::all(), new_free).is_err() {
+				return WithdrawConsequence::Frozen
+			}
+			// Mirrors `transfer`, which reaps leftover dust unless reserved funds keep the
+			// account alive
+			let new_total = new_free.saturating_add(account.locked);
+			if !new_total.is_zero() &&
+				new_total < T::ExistentialDeposit::get() &&
+				account.locked.is_zero()
+			{
+				return WithdrawConsequence::ReducedToZero(new_free)
+			}
+			WithdrawConsequence::Success
+		}
+	}
+
+	impl<T: Config> fungible::Transfer<T::AccountId> for Pallet<T> {
+		fn transfer(
+			source: &T::AccountId,
+			dest: &T::AccountId,
+			amount: T::Balance,
+			keep_alive: bool,
+		) -> Result<T::Balance, DispatchError> {
+			let requirement = if keep_alive {
+				ExistenceRequirement::KeepAlive
+			} else {
+				ExistenceRequirement::AllowDeath
+			};
+			<Self as Currency<_>>::transfer(source, dest, amount, requirement).map(|_| amount)
+		}
+	}
+
+	/// Holds are a named reserve under `HoldReserveId`, so they never release funds that
+	/// other subsystems reserved on the same account
+	impl<T: Config> fungible::InspectHold<T::AccountId> for Pallet<T> {
+		fn balance_on_hold(who: &T::AccountId) -> T::Balance {
+			<Self as NamedReservableCurrency<_>>::reserved_balance_named(
+				&T::HoldReserveId::get(),
+				who,
+			)
+		}
+
+		fn can_hold(who: &T::AccountId, amount: T::Balance) -> bool {
+			<Self as ReservableCurrency<_>>::can_reserve(who, amount)
+		}
+	}
+
+	impl<T: Config> fungible::MutateHold<T::AccountId> for Pallet<T> {
+		fn hold(who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			let id = T::HoldReserveId::get();
+			<Self as NamedReservableCurrency<_>>::reserve_named(&id, who, amount)
+		}
+
+		fn release(
+			who: &T::AccountId,
+			amount: T::Balance,
+			best_effort: bool,
+		) -> Result<T::Balance, DispatchError> {
+			let id = T::HoldReserveId::get();
+			let held = <Self as fungible::InspectHold<_>>::balance_on_hold(who);
+			ensure!(best_effort || held >= amount, Error::<T>::InsufficientBalance);
+			let remaining = <Self as NamedReservableCurrency<_>>::unreserve_named(&id, who, amount);
+			Ok(amount - remaining)
+		}
+
+		fn transfer_held(
+			source: &T::AccountId,
+			dest: &T::AccountId,
+			amount: T::Balance,
+			best_effort: bool,
+			on_hold: bool,
+		) -> Result<T::Balance, DispatchError> {
+			let id = T::HoldReserveId::get();
+			let held = <Self as fungible::InspectHold<_>>::balance_on_hold(source);
+			ensure!(best_effort || held >= amount, Error::<T>::InsufficientBalance);
+			let status = if on_hold { BalanceStatus::Reserved } else { BalanceStatus::Free };
+			let remaining = <Self as NamedReservableCurrency<_>>::repatriate_reserved_named(
+				&id, source, dest, amount, status,
+			)?;
+			Ok(amount - remaining)
+		}
+	}
 } // End of pallet
@@ -0,0 +1,125 @@
+use crate as pallet_kryptokurrency;
+use frame_support::{
+	parameter_types,
+	traits::{GenesisBuild, Imbalance, OnUnbalanced},
+};
+use frame_system as system;
+use pallet_kryptokurrency::{NegativeImbalance, PositiveImbalance};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		KryptoKurrency: pallet_kryptokurrency,
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+/// Supply cap of the mock
+pub const MAX_TOKEN_SUPPLY: u64 = 1_000_000;
+/// Free balance each of the genesis accounts 1 and 2 starts with
+pub const ENDOWMENT: u64 = 1_000;
+
+parameter_types! {
+	pub const MaxTokenSupply: u64 = MAX_TOKEN_SUPPLY;
+	// Tests around the existential deposit raise it with `ExistentialDeposit::set`
+	pub static ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 2;
+	pub const MaxLocks: u32 = 4;
+	// Amounts the handlers below were given, so tests can see where imbalances went
+	pub static MintedToHandler: u64 = 0;
+	pub static SlashedToHandler: u64 = 0;
+	// Dust is burned unless a test sets a trap
+	pub static DustTrap: Option<u64> = None;
+	pub const HoldReserveId: [u8; 8] = *b"fun/hold";
+	pub const DisputeReserveId: [u8; 8] = *b"disputes";
+}
+
+/// Records minted imbalances before dropping them, which keeps them in `TotalIssuance`
+pub struct RecordMint;
+impl OnUnbalanced<PositiveImbalance<Test>> for RecordMint {
+	fn on_nonzero_unbalanced(amount: PositiveImbalance<Test>) {
+		MintedToHandler::set(MintedToHandler::get() + amount.peek());
+	}
+}
+
+/// Records slashed imbalances before dropping them, which burns them out of `TotalIssuance`
+pub struct RecordSlash;
+impl OnUnbalanced<NegativeImbalance<Test>> for RecordSlash {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance<Test>) {
+		SlashedToHandler::set(SlashedToHandler::get() + amount.peek());
+	}
+}
+
+impl pallet_kryptokurrency::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type MaxTokenSupply = MaxTokenSupply;
+	type ExistentialDeposit = ExistentialDeposit;
+	type ReserveIdentifier = [u8; 8];
+	type MaxReserves = MaxReserves;
+	type MaxLocks = MaxLocks;
+	type AdminOrigin = system::EnsureRoot<u64>;
+	type MintHandler = RecordMint;
+	type SlashHandler = RecordSlash;
+	type DustTrap = DustTrap;
+	type HoldReserveId = HoldReserveId;
+	type DisputeReserveId = DisputeReserveId;
+}
+
+// Build genesis storage according to the mock runtime
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_kryptokurrency::GenesisConfig::<Test> {
+		balances: vec![(1, ENDOWMENT, 0), (2, ENDOWMENT, 0)],
+		max_token_supply: None,
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	let mut ext = sp_io::TestExternalities::new(storage);
+	// Events are only recorded from block 1 on
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,1099 @@
+use crate::{
+	mock::*, AccountData, AccountStore, BalanceLock, Error, Event as KkEvent, GenesisConfig,
+	NegativeImbalance, PositiveImbalance, Reasons, TotalIssuance, VestingInfo,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{
+		fungible::{self, InspectHold, MutateHold},
+		Currency,
+		ExistenceRequirement::{AllowDeath, KeepAlive},
+		GenesisBuild, Get, GetStorageVersion, Imbalance, LockableCurrency, NamedReservableCurrency,
+		OnRuntimeUpgrade, ReservableCurrency, SignedImbalance, StorageVersion, TryDrop,
+		WithdrawReasons,
+	},
+};
+use sp_runtime::DispatchError;
+
+mod imbalance_properties;
+
+#[test]
+fn genesis_endows_accounts() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			AccountStore::<Test>::get(1),
+			Some(AccountData { free: ENDOWMENT, ..Default::default() })
+		);
+		assert_eq!(KryptoKurrency::account_of(2).map(|data| data.free), Some(ENDOWMENT));
+		assert_eq!(KryptoKurrency::account_of(3), None);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Total sum in endowed accounts cannot exceed MaxTokenSupply")]
+fn genesis_rejects_endowments_over_max_token_supply() {
+	GenesisConfig::<Test> {
+		balances: vec![(1, MAX_TOKEN_SUPPLY, 0), (2, 1, 0)],
+		max_token_supply: None,
+	}
+	.build_storage()
+	.unwrap();
+}
+
+#[test]
+#[should_panic(expected = "genesis balances overflow Balance type")]
+fn genesis_rejects_balances_overflowing_the_balance_type() {
+	GenesisConfig::<Test> { balances: vec![(1, u64::MAX, 0), (2, 1, 0)], max_token_supply: None }
+		.build_storage()
+		.unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Duplicate entries for accounts in genesis")]
+fn genesis_rejects_duplicate_accounts() {
+	GenesisConfig::<Test> { balances: vec![(1, 10, 0), (1, 20, 0)], max_token_supply: None }
+		.build_storage()
+		.unwrap();
+}
+
+#[test]
+fn genesis_endows_locked_balances() {
+	let storage = GenesisConfig::<Test> { balances: vec![(1, 100, 50)], max_token_supply: None }
+		.build_storage()
+		.unwrap();
+	sp_io::TestExternalities::new(storage).execute_with(|| {
+		assert_eq!(AccountStore::<Test>::get(1), Some(AccountData { free: 100, locked: 50 }));
+		assert_eq!(KryptoKurrency::total_issuance(), 150);
+		// `locked` is the reserved part, no balance lock is placed on the free one
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 50);
+		assert!(KryptoKurrency::locks(1).is_empty());
+		assert_eq!(KryptoKurrency::usable_balance(&1), 100);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Total sum in endowed accounts cannot exceed MaxTokenSupply")]
+fn genesis_counts_locked_balances_towards_max_token_supply() {
+	GenesisConfig::<Test> { balances: vec![(1, MAX_TOKEN_SUPPLY, 1)], max_token_supply: None }
+		.build_storage()
+		.unwrap();
+}
+
+#[test]
+fn total_issuance_starts_at_zero_on_a_fresh_chain() {
+	let storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	sp_io::TestExternalities::new(storage).execute_with(|| {
+		assert!(!TotalIssuance::<Test>::exists());
+		assert_eq!(KryptoKurrency::total_issuance(), 0);
+	});
+}
+
+#[test]
+fn genesis_issuance_is_the_sum_of_endowments() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+	});
+}
+
+#[test]
+fn dropped_imbalances_change_total_issuance() {
+	new_test_ext().execute_with(|| {
+		drop(PositiveImbalance::<Test>::for_test(500));
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT + 500);
+		drop(NegativeImbalance::<Test>::for_test(300));
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT + 200);
+		// Issuance saturates at zero rather than wrapping
+		drop(NegativeImbalance::<Test>::for_test(u64::MAX));
+		assert_eq!(KryptoKurrency::total_issuance(), 0);
+	});
+}
+
+const STAKING: [u8; 8] = *b"staking ";
+const ELECTION: [u8; 8] = *b"election";
+const PREIMAGE: [u8; 8] = *b"preimage";
+
+#[test]
+fn named_reserves_are_tracked_independently() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::reserve_named(&STAKING, &1, 100));
+		assert_ok!(KryptoKurrency::reserve_named(&ELECTION, &1, 30));
+		assert_eq!(KryptoKurrency::reserved_balance_named(&STAKING, &1), 100);
+		assert_eq!(KryptoKurrency::reserved_balance_named(&ELECTION, &1), 30);
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 130);
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 130);
+
+		// Releasing one id leaves the other in place
+		assert_eq!(KryptoKurrency::unreserve_named(&STAKING, &1, 150), 50);
+		assert_eq!(KryptoKurrency::reserved_balance_named(&STAKING, &1), 0);
+		assert_eq!(KryptoKurrency::reserved_balance_named(&ELECTION, &1), 30);
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 30);
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 30);
+	});
+}
+
+#[test]
+fn named_reserves_are_bounded_by_max_reserves() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::reserve_named(&STAKING, &1, 10));
+		assert_ok!(KryptoKurrency::reserve_named(&ELECTION, &1, 10));
+		assert_noop!(
+			KryptoKurrency::reserve_named(&PREIMAGE, &1, 10),
+			Error::<Test>::TooManyReserves
+		);
+		// Topping up an existing id doesn't take a new slot
+		assert_ok!(KryptoKurrency::reserve_named(&STAKING, &1, 10));
+		assert_eq!(KryptoKurrency::reserved_balance_named(&STAKING, &1), 20);
+	});
+}
+
+#[test]
+fn named_reserve_needs_free_funds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KryptoKurrency::reserve_named(&STAKING, &1, ENDOWMENT + 1),
+			Error::<Test>::InsufficientBalance
+		);
+		assert!(KryptoKurrency::reserves_of(1).is_empty());
+	});
+}
+
+#[test]
+fn slash_spills_from_free_into_reserved_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::reserve(&1, 400));
+		let (imbalance, shortfall) = KryptoKurrency::slash(&1, 800);
+		assert_eq!((imbalance.peek(), shortfall), (800, 0));
+		assert_eq!(KryptoKurrency::free_balance(&1), 0);
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 200);
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::Slashed(1, 800)));
+
+		// The slashed funds leave circulation once the imbalance is dropped
+		drop(imbalance);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 800);
+	});
+}
+
+#[test]
+fn slash_reports_what_it_could_not_take() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::reserve(&1, 400));
+		let (imbalance, shortfall) = KryptoKurrency::slash(&1, ENDOWMENT + 50);
+		assert_eq!((imbalance.peek(), shortfall), (ENDOWMENT, 50));
+		assert_eq!(KryptoKurrency::total_balance(&1), 0);
+		drop(imbalance);
+	});
+}
+
+#[test]
+fn slash_reserved_only_takes_reserved_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::reserve(&1, 400));
+		let (imbalance, shortfall) = KryptoKurrency::slash_reserved(&1, 500);
+		assert_eq!((imbalance.peek(), shortfall), (400, 100));
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 400);
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::Slashed(1, 400)));
+		drop(imbalance);
+	});
+}
+
+#[test]
+fn disputes_won_by_the_opener_release_its_funds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KryptoKurrency::open_dispute(Origin::signed(1), 1, 300),
+			Error::<Test>::DisputeWithSelf
+		);
+		assert_ok!(KryptoKurrency::open_dispute(Origin::signed(1), 2, 300));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::DisputeOpened {
+			id: 0,
+			opener: 1,
+			counterparty: 2,
+			amount: 300,
+		}));
+		assert_eq!(KryptoKurrency::reserved_balance_named(&DisputeReserveId::get(), &1), 300);
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 300);
+
+		assert_noop!(
+			KryptoKurrency::resolve_dispute(Origin::signed(2), 0, 2),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			KryptoKurrency::resolve_dispute(Origin::root(), 0, 3),
+			Error::<Test>::NotDisputeParty
+		);
+		assert_ok!(KryptoKurrency::resolve_dispute(Origin::root(), 0, 1));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::DisputeResolved {
+			id: 0,
+			winner: 1,
+			awarded: 300,
+		}));
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT);
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 0);
+		assert_eq!(KryptoKurrency::dispute(0), None);
+		assert_noop!(
+			KryptoKurrency::resolve_dispute(Origin::root(), 0, 1),
+			Error::<Test>::UnknownDispute
+		);
+	});
+}
+
+#[test]
+fn disputes_won_by_the_counterparty_move_the_reserved_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::open_dispute(Origin::signed(1), 2, 300));
+		assert_ok!(KryptoKurrency::resolve_dispute(Origin::root(), 0, 2));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::DisputeResolved {
+			id: 0,
+			winner: 2,
+			awarded: 300,
+		}));
+		assert_eq!(KryptoKurrency::total_balance(&1), ENDOWMENT - 300);
+		assert_eq!(KryptoKurrency::free_balance(&2), ENDOWMENT + 300);
+		// Funds changed hands, none were created or destroyed
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+	});
+}
+
+#[test]
+fn disputes_only_award_what_is_left_of_the_reserve() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::open_dispute(Origin::signed(1), 2, 300));
+		// Something else slashes part of the reserve before the dispute is resolved
+		let (slashed, missing) =
+			KryptoKurrency::slash_reserved_named(&DisputeReserveId::get(), &1, 100);
+		assert_eq!((slashed.peek(), missing), (100, 0));
+		drop(slashed);
+
+		assert_ok!(KryptoKurrency::resolve_dispute(Origin::root(), 0, 2));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::DisputeResolved {
+			id: 0,
+			winner: 2,
+			awarded: 200,
+		}));
+		assert_eq!(KryptoKurrency::free_balance(&2), ENDOWMENT + 200);
+		assert_eq!(KryptoKurrency::total_balance(&1), ENDOWMENT - 300);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 100);
+	});
+}
+
+#[test]
+fn deposit_into_existing_needs_an_existing_account() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(KryptoKurrency::deposit_into_existing(&3, 100), Error::<Test>::DeadAccount);
+		let imbalance = KryptoKurrency::deposit_into_existing(&1, 100).unwrap();
+		assert_eq!(imbalance.peek(), 100);
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT + 100);
+		drop(imbalance);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT + 100);
+	});
+}
+
+/// How many times `event` was deposited so far
+fn count_of(event: KkEvent<Test>) -> usize {
+	let event = Event::KryptoKurrency(event);
+	System::events().iter().filter(|record| record.event == event).count()
+}
+
+#[test]
+fn deposits_and_withdrawals_emit_one_event_per_call() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..3 {
+			drop(KryptoKurrency::deposit_into_existing(&1, 10).unwrap());
+			drop(KryptoKurrency::deposit_creating(&3, 10));
+			drop(KryptoKurrency::withdraw(&2, 10, WithdrawReasons::all(), KeepAlive).unwrap());
+		}
+		assert_eq!(count_of(KkEvent::Deposit { who: 1, amount: 10 }), 3);
+		assert_eq!(count_of(KkEvent::Deposit { who: 3, amount: 10 }), 3);
+		assert_eq!(count_of(KkEvent::Withdraw { who: 2, amount: 10 }), 3);
+	});
+}
+
+#[test]
+fn failed_deposits_and_withdrawals_emit_nothing() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert!(KryptoKurrency::deposit_into_existing(&3, 100).is_err());
+		drop(KryptoKurrency::deposit_creating(&3, 9));
+		assert!(KryptoKurrency::withdraw(&1, ENDOWMENT + 1, WithdrawReasons::all(), AllowDeath)
+			.is_err());
+		// Zero amounts don't touch the account either
+		drop(KryptoKurrency::deposit_creating(&1, 0));
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn deposit_creating_creates_the_account() {
+	new_test_ext().execute_with(|| {
+		let imbalance = KryptoKurrency::deposit_creating(&3, 100);
+		assert_eq!(imbalance.peek(), 100);
+		assert_eq!(KryptoKurrency::free_balance(&3), 100);
+		drop(imbalance);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT + 100);
+	});
+}
+
+#[test]
+fn deposit_creating_below_the_existential_deposit_does_nothing() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		let imbalance = KryptoKurrency::deposit_creating(&3, 9);
+		assert_eq!(imbalance.peek(), 0);
+		assert_eq!(KryptoKurrency::account_of(3), None);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+		// Existing accounts may still receive less than that
+		assert_eq!(KryptoKurrency::deposit_creating(&1, 9).peek(), 9);
+	});
+}
+
+const VESTING: [u8; 8] = *b"vesting ";
+
+#[test]
+fn withdraw_keeps_the_account_alive_unless_allowed_to_kill_it() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KryptoKurrency::withdraw(&1, ENDOWMENT, WithdrawReasons::TRANSFER, KeepAlive),
+			Error::<Test>::KeepAlive
+		);
+		let imbalance =
+			KryptoKurrency::withdraw(&1, ENDOWMENT - 1, WithdrawReasons::TRANSFER, KeepAlive)
+				.unwrap();
+		assert_eq!(imbalance.peek(), ENDOWMENT - 1);
+		assert_eq!(KryptoKurrency::free_balance(&1), 1);
+		drop(imbalance);
+
+		assert_ok!(KryptoKurrency::withdraw(&1, 1, WithdrawReasons::TRANSFER, AllowDeath));
+		assert_eq!(KryptoKurrency::account_of(1), None);
+	});
+}
+
+#[test]
+fn withdraw_never_leaves_dust_below_the_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert_noop!(
+			KryptoKurrency::withdraw(&1, ENDOWMENT - 5, WithdrawReasons::TRANSFER, AllowDeath),
+			Error::<Test>::ExistentialDeposit
+		);
+		assert_ok!(KryptoKurrency::withdraw(
+			&1,
+			ENDOWMENT - 10,
+			WithdrawReasons::TRANSFER,
+			KeepAlive
+		));
+	});
+}
+
+#[test]
+fn withdraw_is_blocked_by_a_lock() {
+	new_test_ext().execute_with(|| {
+		KryptoKurrency::set_lock(VESTING, &1, 600, WithdrawReasons::all());
+		assert_noop!(
+			KryptoKurrency::withdraw(&1, 401, WithdrawReasons::TRANSFER, KeepAlive),
+			Error::<Test>::LiquidityRestrictions
+		);
+		assert_ok!(KryptoKurrency::withdraw(&1, 400, WithdrawReasons::TRANSFER, KeepAlive));
+
+		// A lock only restricts the withdrawals it was set for
+		KryptoKurrency::set_lock(VESTING, &2, 600, WithdrawReasons::TRANSACTION_PAYMENT);
+		assert_ok!(KryptoKurrency::withdraw(&2, 900, WithdrawReasons::TRANSFER, KeepAlive));
+
+		KryptoKurrency::remove_lock(VESTING, &1);
+		assert_ok!(KryptoKurrency::withdraw(&1, 599, WithdrawReasons::TRANSFER, KeepAlive));
+	});
+}
+
+#[test]
+fn make_free_balance_be_tracks_the_net_change_in_issuance() {
+	new_test_ext().execute_with(|| {
+		drop(KryptoKurrency::make_free_balance_be(&1, ENDOWMENT + 300));
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT + 300);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT + 300);
+
+		drop(KryptoKurrency::make_free_balance_be(&1, ENDOWMENT - 200));
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 200);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 200);
+
+		drop(KryptoKurrency::make_free_balance_be(&3, 50));
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 150);
+	});
+}
+
+#[test]
+fn make_free_balance_be_reaps_balances_below_the_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		drop(KryptoKurrency::make_free_balance_be(&1, 9));
+		assert_eq!(KryptoKurrency::account_of(1), None);
+		assert_eq!(KryptoKurrency::total_issuance(), ENDOWMENT);
+
+		// Nor is a new account created for it
+		match KryptoKurrency::make_free_balance_be(&3, 9) {
+			SignedImbalance::Positive(imbalance) => assert_eq!(imbalance.peek(), 0),
+			SignedImbalance::Negative(_) => panic!("nothing was taken from account 3"),
+		}
+		assert_eq!(KryptoKurrency::account_of(3), None);
+	});
+}
+
+#[test]
+fn mint_into_hands_the_imbalance_to_the_mint_handler() {
+	new_test_ext().execute_with(|| {
+		let events = System::events().len();
+		KryptoKurrency::mint_into(&3, 250);
+		assert_eq!(MintedToHandler::get(), 250);
+		assert_eq!(KryptoKurrency::free_balance(&3), 250);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT + 250);
+		// Reported once, when the imbalance grows the supply
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::Issued(250, 2 * ENDOWMENT + 250)));
+		assert_eq!(System::events().len(), events + 1);
+	});
+}
+
+#[test]
+fn mint_into_stops_at_the_supply_cap() {
+	new_test_ext().execute_with(|| {
+		let headroom = MAX_TOKEN_SUPPLY - 2 * ENDOWMENT;
+		KryptoKurrency::mint_into(&3, headroom + 500);
+		assert_eq!(KryptoKurrency::free_balance(&3), headroom);
+		assert_eq!(KryptoKurrency::total_issuance(), MAX_TOKEN_SUPPLY);
+
+		// Nothing left to mint, so nothing happens at all
+		let events = System::events().len();
+		KryptoKurrency::mint_into(&1, 10);
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT);
+		assert_eq!(KryptoKurrency::total_issuance(), MAX_TOKEN_SUPPLY);
+		assert_eq!(System::events().len(), events);
+	});
+}
+
+#[test]
+fn deposits_that_mint_stop_at_the_supply_cap_but_resolving_does_not() {
+	new_test_ext().execute_with(|| {
+		let headroom = MAX_TOKEN_SUPPLY - 2 * ENDOWMENT;
+		let imbalance = KryptoKurrency::deposit_creating(&3, headroom + 100);
+		assert_eq!(imbalance.peek(), headroom);
+		drop(imbalance);
+		assert_eq!(KryptoKurrency::total_issuance(), MAX_TOKEN_SUPPLY);
+
+		assert_noop!(
+			KryptoKurrency::deposit_into_existing(&1, 1),
+			Error::<Test>::MaxTokenSupplyExceeded
+		);
+		assert_eq!(KryptoKurrency::deposit_creating(&4, 100).peek(), 0);
+		drop(KryptoKurrency::make_free_balance_be(&1, ENDOWMENT + 100));
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT);
+		let positive = SignedImbalance::Positive(PositiveImbalance::<Test>::for_test(1));
+		let leftover = match KryptoKurrency::settle(&1, positive) {
+			Err(SignedImbalance::Positive(leftover)) => leftover,
+			_ => panic!("settling would take the supply over the cap"),
+		};
+		// Cancel the test imbalance out, no funds were ever behind it
+		drop(leftover.offset(NegativeImbalance::<Test>::for_test(1)));
+		assert_eq!(KryptoKurrency::total_issuance(), MAX_TOKEN_SUPPLY);
+
+		// Funds that already exist can still move, the supply stays the same
+		let withdrawn =
+			KryptoKurrency::withdraw(&1, 500, WithdrawReasons::all(), AllowDeath).unwrap();
+		KryptoKurrency::resolve_creating(&4, withdrawn);
+		assert_eq!(KryptoKurrency::free_balance(&4), 500);
+		assert_eq!(KryptoKurrency::total_issuance(), MAX_TOKEN_SUPPLY);
+	});
+}
+
+#[test]
+fn slash_into_handler_hands_the_slashed_funds_over() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(KryptoKurrency::slash_into_handler(&1, 300), 0);
+		assert_eq!(SlashedToHandler::get(), 300);
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 300);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 300);
+
+		// Only what was actually slashed reaches the handler
+		assert_eq!(KryptoKurrency::slash_into_handler(&1, ENDOWMENT), 300);
+		assert_eq!(SlashedToHandler::get(), ENDOWMENT);
+	});
+}
+
+fn schedule(locked: u64, per_block: u64, starting_block: u64) -> VestingInfo<u64, u64> {
+	VestingInfo { locked, per_block, starting_block }
+}
+
+#[test]
+fn vested_transfer_locks_everything_until_the_start_block() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::vested_transfer(Origin::signed(1), 3, schedule(100, 10, 5)));
+		assert_eq!(KryptoKurrency::free_balance(&3), 100);
+		assert_eq!(KryptoKurrency::vesting(&3), Some(schedule(100, 10, 5)));
+		assert_noop!(
+			KryptoKurrency::transfer(&3, &4, 1, AllowDeath),
+			Error::<Test>::LiquidityRestrictions
+		);
+
+		System::set_block_number(5);
+		assert_ok!(KryptoKurrency::vest(Origin::signed(3)));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::VestingUpdated(3, 100)));
+		assert_noop!(
+			KryptoKurrency::transfer(&3, &4, 1, AllowDeath),
+			Error::<Test>::LiquidityRestrictions
+		);
+	});
+}
+
+#[test]
+fn vest_releases_the_vested_part_mid_schedule() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::vested_transfer(Origin::signed(1), 3, schedule(100, 10, 5)));
+		System::set_block_number(8);
+		assert_ok!(KryptoKurrency::vest(Origin::signed(3)));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::VestingUpdated(3, 70)));
+		assert_noop!(
+			KryptoKurrency::transfer(&3, &4, 31, AllowDeath),
+			Error::<Test>::LiquidityRestrictions
+		);
+		assert_ok!(KryptoKurrency::transfer(&3, &4, 30, AllowDeath));
+	});
+}
+
+#[test]
+fn vest_drops_the_schedule_once_fully_vested() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::vested_transfer(Origin::signed(1), 3, schedule(100, 10, 5)));
+		System::set_block_number(15);
+		assert_ok!(KryptoKurrency::vest(Origin::signed(3)));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::VestingCompleted(3)));
+		assert_eq!(KryptoKurrency::vesting(&3), None);
+		assert!(KryptoKurrency::locks(&3).is_empty());
+		assert_ok!(KryptoKurrency::transfer(&3, &4, 100, AllowDeath));
+		assert_noop!(KryptoKurrency::vest(Origin::signed(3)), Error::<Test>::NotVesting);
+	});
+}
+
+#[test]
+fn vested_transfer_rejects_bad_schedules() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KryptoKurrency::vested_transfer(Origin::signed(1), 3, schedule(100, 0, 5)),
+			Error::<Test>::InvalidVestingSchedule
+		);
+		assert_ok!(KryptoKurrency::vested_transfer(Origin::signed(1), 3, schedule(100, 10, 5)));
+		assert_noop!(
+			KryptoKurrency::vested_transfer(Origin::signed(2), 3, schedule(100, 10, 5)),
+			Error::<Test>::ExistingVestingSchedule
+		);
+	});
+}
+
+#[test]
+fn balance_getters_split_free_reserved_and_usable() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::reserve(&1, 300));
+		KryptoKurrency::set_lock(VESTING, &1, 500, WithdrawReasons::all());
+		KryptoKurrency::set_lock(*b"staking ", &1, 200, WithdrawReasons::all());
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 300);
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 300);
+		// Locks overlap, so only the largest one counts
+		assert_eq!(KryptoKurrency::usable_balance(&1), ENDOWMENT - 300 - 500);
+		assert_eq!(KryptoKurrency::account_of(1).unwrap().total(), ENDOWMENT);
+
+		// A lock larger than the free balance leaves nothing usable
+		KryptoKurrency::set_lock(VESTING, &1, ENDOWMENT, WithdrawReasons::all());
+		assert_eq!(KryptoKurrency::usable_balance(&1), 0);
+	});
+}
+
+#[test]
+fn balance_getters_read_zero_for_absent_accounts() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(KryptoKurrency::free_balance(&3), 0);
+		assert_eq!(KryptoKurrency::reserved_balance(&3), 0);
+		assert_eq!(KryptoKurrency::usable_balance(&3), 0);
+	});
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn state_structs_round_trip_through_serde() {
+	let account = AccountData { free: 700, locked: 300 };
+	let json = serde_json::to_string(&account).unwrap();
+	assert_eq!(serde_json::from_str::<AccountData<u64>>(&json).unwrap(), account);
+
+	let lock = BalanceLock { id: VESTING, amount: 500, reasons: Reasons::All };
+	let json = serde_json::to_string(&lock).unwrap();
+	assert_eq!(serde_json::from_str::<BalanceLock<u64>>(&json).unwrap(), lock);
+
+	let vesting = schedule(100, 10, 5);
+	let json = serde_json::to_string(&vesting).unwrap();
+	assert_eq!(serde_json::from_str::<VestingInfo<u64, u64>>(&json).unwrap(), vesting);
+}
+
+#[test]
+fn settle_into_credits_the_whole_imbalance() {
+	new_test_ext().execute_with(|| {
+		let imbalance =
+			KryptoKurrency::withdraw(&1, 300, WithdrawReasons::TRANSFER, KeepAlive).unwrap();
+		assert!(KryptoKurrency::settle_into(&3, imbalance).is_ok());
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 300);
+		assert_eq!(KryptoKurrency::free_balance(&3), 300);
+		// Fully handled, so nothing was minted or burned
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+	});
+}
+
+#[test]
+fn settle_into_hands_back_what_it_could_not_credit() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		let imbalance =
+			KryptoKurrency::withdraw(&1, 5, WithdrawReasons::TRANSFER, KeepAlive).unwrap();
+		// Too little to create account 3
+		let leftover = KryptoKurrency::settle_into(&3, imbalance).unwrap_err();
+		assert_eq!(leftover.peek(), 5);
+		let leftover = leftover.try_drop().unwrap_err();
+		assert!(KryptoKurrency::settle_into(&2, leftover).is_ok());
+		assert_eq!(KryptoKurrency::free_balance(&2), ENDOWMENT + 5);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+	});
+}
+
+#[test]
+#[cfg(all(debug_assertions, feature = "strict-imbalances"))]
+#[should_panic(expected = "Nonzero NegativeImbalance dropped without being handled")]
+fn dropping_an_unhandled_imbalance_panics_with_strict_imbalances() {
+	new_test_ext().execute_with(|| {
+		let imbalance =
+			KryptoKurrency::withdraw(&1, 300, WithdrawReasons::TRANSFER, KeepAlive).unwrap();
+		drop(imbalance);
+	});
+}
+
+#[test]
+fn migration_from_v0_records_the_version_and_keeps_balances() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<KryptoKurrency>();
+
+		<KryptoKurrency as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(KryptoKurrency::on_chain_storage_version(), 1);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT);
+
+		// A second run leaves the version where it is
+		<KryptoKurrency as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(KryptoKurrency::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn transfer_with_keep_alive_refuses_to_reap_the_sender() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert_noop!(
+			KryptoKurrency::transfer(&1, &2, ENDOWMENT, KeepAlive),
+			Error::<Test>::KeepAlive
+		);
+		assert_noop!(
+			KryptoKurrency::transfer(&1, &2, ENDOWMENT - 5, KeepAlive),
+			Error::<Test>::KeepAlive
+		);
+		assert_ok!(KryptoKurrency::transfer(&1, &2, ENDOWMENT - 10, KeepAlive));
+		assert_eq!(KryptoKurrency::free_balance(&1), 10);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+	});
+}
+
+#[test]
+fn transfer_allowing_death_reaps_the_sender_and_burns_its_dust() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert_ok!(KryptoKurrency::transfer(&1, &2, ENDOWMENT - 5, AllowDeath));
+		assert_eq!(KryptoKurrency::account_of(1), None);
+		assert_eq!(KryptoKurrency::free_balance(&2), 2 * ENDOWMENT - 5);
+		// The 5 units of dust left the supply along with the account
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 5);
+
+		assert_ok!(KryptoKurrency::transfer(&2, &1, ENDOWMENT, AllowDeath));
+		assert_ok!(KryptoKurrency::transfer(&1, &2, ENDOWMENT, AllowDeath));
+		assert_eq!(KryptoKurrency::account_of(1), None);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 5);
+	});
+}
+
+#[test]
+fn transfer_refuses_to_create_a_recipient_below_the_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert_noop!(
+			KryptoKurrency::transfer(&1, &3, 9, AllowDeath),
+			Error::<Test>::ExistentialDeposit
+		);
+		assert_ok!(KryptoKurrency::transfer(&1, &3, 10, AllowDeath));
+		assert_eq!(KryptoKurrency::free_balance(&3), 10);
+	});
+}
+
+#[test]
+fn reducible_balance_accounts_for_locks_and_keep_alive() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert_eq!(KryptoKurrency::reducible_balance(&1, false), ENDOWMENT);
+		assert_eq!(KryptoKurrency::reducible_balance(&1, true), ENDOWMENT - 10);
+
+		KryptoKurrency::set_lock(VESTING, &1, 300, WithdrawReasons::all());
+		KryptoKurrency::set_lock(*b"staking ", &1, 200, WithdrawReasons::all());
+		// Only the largest lock counts, locks overlap
+		assert_eq!(KryptoKurrency::reducible_balance(&1, false), ENDOWMENT - 300);
+		assert_eq!(KryptoKurrency::reducible_balance(&1, true), ENDOWMENT - 310);
+
+		// A lock over the whole balance leaves nothing, without underflowing
+		KryptoKurrency::set_lock(VESTING, &1, 2 * ENDOWMENT, WithdrawReasons::all());
+		assert_eq!(KryptoKurrency::reducible_balance(&1, false), 0);
+		assert_eq!(KryptoKurrency::reducible_balance(&1, true), 0);
+	});
+}
+
+#[test]
+fn currency_fungible_and_the_getters_agree_on_what_is_spendable() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		KryptoKurrency::set_lock(VESTING, &1, 300, WithdrawReasons::all());
+		for keep_alive in [false, true] {
+			assert_eq!(
+				<KryptoKurrency as fungible::Inspect<_>>::reducible_balance(&1, keep_alive),
+				KryptoKurrency::reducible_balance(&1, keep_alive)
+			);
+		}
+		let spendable = KryptoKurrency::reducible_balance(&1, false);
+		assert_eq!(spendable, ENDOWMENT - 300);
+		assert_eq!(KryptoKurrency::usable_balance(&1), spendable);
+
+		// Currency lets exactly that much go, and not a unit more
+		assert_noop!(
+			KryptoKurrency::withdraw(&1, spendable + 1, WithdrawReasons::all(), AllowDeath),
+			Error::<Test>::LiquidityRestrictions
+		);
+		assert_ok!(KryptoKurrency::withdraw(&1, spendable, WithdrawReasons::all(), AllowDeath));
+		assert_eq!(KryptoKurrency::reducible_balance(&1, false), 0);
+		assert_eq!(<KryptoKurrency as fungible::Inspect<_>>::reducible_balance(&1, false), 0);
+	});
+}
+
+#[test]
+fn report_balance_emits_the_stored_breakdown_of_a_locked_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::reserve(&1, 200));
+		KryptoKurrency::set_lock(VESTING, &1, 300, WithdrawReasons::all());
+		let before = AccountStore::<Test>::get(1);
+
+		assert_ok!(KryptoKurrency::report_balance(Origin::signed(2), 1));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::BalanceReport {
+			who: 1,
+			free: KryptoKurrency::free_balance(&1),
+			locked: KryptoKurrency::reserved_balance(&1),
+			usable: KryptoKurrency::usable_balance(&1),
+		}));
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 200);
+		assert_eq!(KryptoKurrency::usable_balance(&1), KryptoKurrency::free_balance(&1) - 300);
+		// Only an event, storage is left as it was
+		assert_eq!(AccountStore::<Test>::get(1), before);
+	});
+}
+
+#[test]
+fn force_set_balance_moves_issuance_by_the_difference() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KryptoKurrency::force_set_balance(Origin::signed(1), 1, 5_000),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(KryptoKurrency::force_set_balance(Origin::root(), 1, 5_000));
+		assert_eq!(KryptoKurrency::free_balance(&1), 5_000);
+		assert_eq!(KryptoKurrency::total_issuance(), 5_000 + ENDOWMENT);
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::BalanceSet {
+			who: 1,
+			free: 5_000,
+		}));
+
+		assert_ok!(KryptoKurrency::force_set_balance(Origin::root(), 1, 400));
+		assert_eq!(KryptoKurrency::free_balance(&1), 400);
+		assert_eq!(KryptoKurrency::total_issuance(), 400 + ENDOWMENT);
+
+		// New accounts are created and count towards issuance too
+		assert_ok!(KryptoKurrency::force_set_balance(Origin::root(), 3, 100));
+		assert_eq!(KryptoKurrency::total_issuance(), 500 + ENDOWMENT);
+	});
+}
+
+#[test]
+fn force_set_balance_reaps_below_the_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert_ok!(KryptoKurrency::force_set_balance(Origin::root(), 1, 9));
+		assert_eq!(KryptoKurrency::account_of(1), None);
+		assert_eq!(KryptoKurrency::total_issuance(), ENDOWMENT);
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::BalanceSet { who: 1, free: 0 }));
+	});
+}
+
+#[test]
+fn force_set_balance_respects_max_token_supply() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KryptoKurrency::force_set_balance(Origin::root(), 1, MAX_TOKEN_SUPPLY - ENDOWMENT + 1),
+			Error::<Test>::MaxTokenSupplyExceeded
+		);
+		assert_ok!(KryptoKurrency::force_set_balance(
+			Origin::root(),
+			1,
+			MAX_TOKEN_SUPPLY - ENDOWMENT
+		));
+		assert_eq!(KryptoKurrency::total_issuance(), MAX_TOKEN_SUPPLY);
+	});
+}
+
+#[test]
+fn lock_summary_lists_every_active_lock() {
+	new_test_ext().execute_with(|| {
+		assert!(KryptoKurrency::lock_summary(&1).is_empty());
+		KryptoKurrency::set_lock(VESTING, &1, 300, WithdrawReasons::all());
+		KryptoKurrency::set_lock(*b"staking ", &1, 200, WithdrawReasons::TRANSFER);
+		assert_eq!(KryptoKurrency::lock_summary(&1), vec![(VESTING, 300), (*b"staking ", 200)]);
+
+		KryptoKurrency::remove_lock(VESTING, &1);
+		assert_eq!(KryptoKurrency::lock_summary(&1), vec![(*b"staking ", 200)]);
+	});
+}
+
+#[test]
+fn settle_deposits_a_positive_imbalance_into_a_new_account() {
+	new_test_ext().execute_with(|| {
+		let imbalance = SignedImbalance::Positive(PositiveImbalance::<Test>::for_test(300));
+		assert!(KryptoKurrency::settle(&3, imbalance).is_ok());
+		assert_eq!(KryptoKurrency::free_balance(&3), 300);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT + 300);
+	});
+}
+
+#[test]
+fn settle_hands_back_a_positive_imbalance_it_cannot_deposit() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		let imbalance = SignedImbalance::Positive(PositiveImbalance::<Test>::for_test(5));
+		let leftover = KryptoKurrency::settle(&3, imbalance).unwrap_err();
+		assert_eq!(KryptoKurrency::account_of(3), None);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+		assert!(KryptoKurrency::settle(&1, leftover).is_ok());
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT + 5);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT + 5);
+	});
+}
+
+#[test]
+fn settle_withdraws_a_negative_imbalance_as_far_as_the_balance_goes() {
+	new_test_ext().execute_with(|| {
+		let imbalance =
+			SignedImbalance::Negative(NegativeImbalance::<Test>::for_test(ENDOWMENT + 500));
+		let leftover = match KryptoKurrency::settle(&1, imbalance) {
+			Err(SignedImbalance::Negative(leftover)) => leftover,
+			_ => panic!("the part over account 1's balance should be handed back"),
+		};
+		assert_eq!(leftover.peek(), 500);
+		assert_eq!(KryptoKurrency::account_of(1), None);
+		assert_eq!(KryptoKurrency::total_issuance(), ENDOWMENT);
+
+		assert!(KryptoKurrency::settle(&2, SignedImbalance::Negative(leftover)).is_ok());
+		assert_eq!(KryptoKurrency::free_balance(&2), ENDOWMENT - 500);
+		assert_eq!(KryptoKurrency::total_issuance(), ENDOWMENT - 500);
+	});
+}
+
+#[test]
+fn balance_conversions_refuse_to_saturate() {
+	assert_eq!(KryptoKurrency::balance_to_u128(u64::MAX), Some(u64::MAX as u128));
+	assert_eq!(KryptoKurrency::u128_to_balance(u64::MAX as u128), Some(u64::MAX));
+	assert_eq!(KryptoKurrency::u128_to_balance(u64::MAX as u128 + 1), None);
+	assert_eq!(KryptoKurrency::u128_to_balance(u128::MAX), None);
+	assert_eq!(KryptoKurrency::u128_to_balance(0), Some(0));
+}
+
+#[test]
+fn reaped_dust_is_burned_without_a_trap() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert_ok!(KryptoKurrency::transfer(&1, &2, ENDOWMENT - 5, AllowDeath));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::DustLost(1, 5)));
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 5);
+	});
+}
+
+#[test]
+fn reaped_dust_goes_to_the_trap() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		DustTrap::set(Some(3));
+		assert_ok!(KryptoKurrency::transfer(&1, &2, ENDOWMENT - 5, AllowDeath));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::Transfer(1, 3, 5)));
+		assert_eq!(KryptoKurrency::account_of(1), None);
+		assert_eq!(KryptoKurrency::free_balance(&3), 5);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+	});
+}
+
+#[test]
+fn dropped_imbalances_report_supply_changes() {
+	new_test_ext().execute_with(|| {
+		drop(KryptoKurrency::deposit_creating(&3, 300));
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::Issued(300, 2 * ENDOWMENT + 300)));
+
+		drop(KryptoKurrency::withdraw(&1, 100, WithdrawReasons::TRANSFER, KeepAlive).unwrap());
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::Rescinded(
+			100,
+			2 * ENDOWMENT + 200,
+		)));
+	});
+}
+
+#[test]
+fn settled_imbalances_report_supply_changes() {
+	new_test_ext().execute_with(|| {
+		let imbalance = SignedImbalance::Positive(PositiveImbalance::<Test>::for_test(300));
+		assert!(KryptoKurrency::settle(&3, imbalance).is_ok());
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::Issued(300, 2 * ENDOWMENT + 300)));
+
+		let imbalance = SignedImbalance::Negative(NegativeImbalance::<Test>::for_test(100));
+		assert!(KryptoKurrency::settle(&3, imbalance).is_ok());
+		System::assert_last_event(Event::KryptoKurrency(KkEvent::Rescinded(
+			100,
+			2 * ENDOWMENT + 200,
+		)));
+	});
+}
+
+#[test]
+fn zero_imbalances_stay_silent() {
+	new_test_ext().execute_with(|| {
+		drop(PositiveImbalance::<Test>::for_test(0));
+		drop(NegativeImbalance::<Test>::for_test(0));
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn account_data_returns_free_and_locked_together() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::reserve(&1, 400));
+		assert_eq!(KryptoKurrency::account_data(&1), AccountData { free: 600, locked: 400 });
+		// Absent accounts read as the default
+		assert_eq!(KryptoKurrency::account_data(&3), AccountData::default());
+	});
+}
+
+#[test]
+fn deposits_are_collected_and_refunded() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::collect_deposit(&1, 300));
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 300);
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 300);
+
+		// Refunding more than was collected hands back the difference
+		assert_eq!(KryptoKurrency::refund_deposit(&1, 400), 100);
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT);
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 0);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+	});
+}
+
+#[test]
+fn collect_deposit_fails_over_the_free_balance() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KryptoKurrency::collect_deposit(&1, ENDOWMENT + 1),
+			Error::<Test>::InsufficientBalance
+		);
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 0);
+	});
+}
+
+#[test]
+fn slashed_funds_reward_the_reporter_and_burn_the_rest() {
+	new_test_ext().execute_with(|| {
+		let (slashed, shortfall) = KryptoKurrency::slash(&1, 100);
+		assert_eq!(shortfall, 0);
+		assert!(KryptoKurrency::reward_from_slash(&3, slashed, 10).is_ok());
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 100);
+		assert_eq!(KryptoKurrency::free_balance(&3), 10);
+		assert_eq!(SlashedToHandler::get(), 90);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 90);
+	});
+}
+
+#[test]
+fn reward_from_slash_hands_back_what_the_reporter_cannot_take() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(20);
+		let (slashed, _) = KryptoKurrency::slash(&1, 100);
+		// Too little to create the reporter's account
+		let leftover = KryptoKurrency::reward_from_slash(&3, slashed, 10).unwrap_err();
+		assert_eq!(leftover.peek(), 10);
+		assert_eq!(SlashedToHandler::get(), 90);
+		assert_eq!(KryptoKurrency::account_of(3), None);
+		drop(leftover);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT - 100);
+	});
+}
+
+#[test]
+fn account_exists_until_the_account_is_reaped() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		assert!(KryptoKurrency::account_exists(&1));
+		assert!(!KryptoKurrency::account_exists(&3));
+
+		assert_ok!(KryptoKurrency::transfer(&1, &2, ENDOWMENT - 5, AllowDeath));
+		assert!(!KryptoKurrency::account_exists(&1));
+		assert!(KryptoKurrency::account_exists(&2));
+	});
+}
+
+#[test]
+fn fungible_holds_can_be_placed_released_and_transferred() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KryptoKurrency::reserve_named(&STAKING, &1, 50));
+		assert_ok!(KryptoKurrency::hold(&1, 100));
+		assert_eq!(KryptoKurrency::balance_on_hold(&1), 100);
+		assert_eq!(KryptoKurrency::reserved_balance(&1), 150);
+		assert!(!KryptoKurrency::can_hold(&1, ENDOWMENT));
+
+		assert_eq!(KryptoKurrency::release(&1, 40, false), Ok(40));
+		assert_noop!(KryptoKurrency::release(&1, 61, false), Error::<Test>::InsufficientBalance);
+		assert_eq!(KryptoKurrency::balance_on_hold(&1), 60);
+		assert_eq!(KryptoKurrency::free_balance(&1), ENDOWMENT - 110);
+		// Reserves under other ids are never touched by holds
+		assert_eq!(KryptoKurrency::reserved_balance_named(&STAKING, &1), 50);
+
+		assert_eq!(KryptoKurrency::transfer_held(&1, &2, 20, false, true), Ok(20));
+		assert_eq!(KryptoKurrency::transfer_held(&1, &2, 15, false, false), Ok(15));
+		assert_eq!(KryptoKurrency::balance_on_hold(&1), 25);
+		assert_eq!(KryptoKurrency::balance_on_hold(&2), 20);
+		assert_eq!(KryptoKurrency::free_balance(&2), ENDOWMENT + 15);
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT);
+	});
+}
+
+#[test]
+fn imbalances_merge_and_split_by_amount() {
+	new_test_ext().execute_with(|| {
+		let merged = PositiveImbalance::<Test>::for_test(30).merge(PositiveImbalance::for_test(12));
+		assert_eq!(merged.peek(), 42);
+		let (first, second) = merged.split(40);
+		assert_eq!((first.peek(), second.peek()), (40, 2));
+
+		// Splitting off more than there is takes all of it
+		let (all, rest) = NegativeImbalance::<Test>::for_test(5).split(9);
+		assert_eq!(all.peek(), 5);
+		assert_eq!(rest, NegativeImbalance::zero());
+
+		drop((first, second, all, rest));
+		assert_eq!(KryptoKurrency::total_issuance(), 2 * ENDOWMENT + 42 - 5);
+	});
+}
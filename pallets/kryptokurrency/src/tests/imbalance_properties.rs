@@ -0,0 +1,128 @@
+//! Property tests for the imbalance types, each case runs on a fresh mock runtime
+//! Run them with `cargo test -p pallet-kryptokurrency imbalance_properties`, and set
+//! `PROPTEST_CASES=10000` to dig deeper than the default 256 cases per property
+
+use crate::{mock::*, NegativeImbalance, PositiveImbalance, TotalIssuance};
+use frame_support::traits::{Currency, Imbalance, SameOrOther};
+use proptest::prelude::*;
+
+/// Amounts up to a quarter of `u64::MAX`, so that sums of a few of them never saturate
+fn amount() -> impl Strategy<Value = u64> {
+	0..u64::MAX / 4
+}
+
+/// Amounts around the mock's supply cap, so that issuing and burning run into their clamps
+fn capped_amount() -> impl Strategy<Value = u64> {
+	0..MAX_TOKEN_SUPPLY * 2
+}
+
+proptest! {
+	#[test]
+	fn split_preserves_amount(total in amount(), at in amount()) {
+		new_test_ext().execute_with(|| {
+			let (first, second) = PositiveImbalance::<Test>::for_test(total).split(at);
+			assert_eq!(first.peek(), at.min(total));
+			assert_eq!(first.peek() + second.peek(), total);
+			let (first, second) = NegativeImbalance::<Test>::for_test(total).split(at);
+			assert_eq!(first.peek(), at.min(total));
+			assert_eq!(first.peek() + second.peek(), total);
+		});
+	}
+
+	#[test]
+	fn split_halves_drop_like_the_whole(total in amount(), at in amount()) {
+		new_test_ext().execute_with(|| {
+			let before = TotalIssuance::<Test>::get();
+			let (first, second) = PositiveImbalance::<Test>::for_test(total).split(at);
+			drop(first);
+			drop(second);
+			assert_eq!(TotalIssuance::<Test>::get(), before + total);
+		});
+	}
+
+	#[test]
+	fn merge_and_subsume_preserve_amount(a in amount(), b in amount()) {
+		new_test_ext().execute_with(|| {
+			let before = TotalIssuance::<Test>::get();
+			let merged = PositiveImbalance::<Test>::for_test(a).merge(PositiveImbalance::for_test(b));
+			assert_eq!(merged.peek(), a + b);
+			let mut subsumed = NegativeImbalance::<Test>::for_test(a);
+			subsumed.subsume(NegativeImbalance::for_test(b));
+			assert_eq!(subsumed.peek(), a + b);
+			// Only the merged imbalances may touch issuance, their parts must not
+			TotalIssuance::<Test>::put(before + a + b);
+			drop(subsumed);
+			assert_eq!(TotalIssuance::<Test>::get(), before);
+			drop(merged);
+			assert_eq!(TotalIssuance::<Test>::get(), before + a + b);
+		});
+	}
+
+	#[test]
+	fn offset_leaves_the_difference(a in amount(), b in amount()) {
+		new_test_ext().execute_with(|| {
+			let before = TotalIssuance::<Test>::get();
+			match PositiveImbalance::<Test>::for_test(a).offset(NegativeImbalance::for_test(b)) {
+				SameOrOther::Same(positive) => {
+					assert!(a > b);
+					assert_eq!(positive.peek(), a - b);
+				},
+				SameOrOther::Other(negative) => {
+					assert!(a < b);
+					assert_eq!(negative.peek(), b - a);
+					TotalIssuance::<Test>::put(before + negative.peek());
+				},
+				SameOrOther::None => assert_eq!(a, b),
+			}
+			assert_eq!(TotalIssuance::<Test>::get(), before + a.saturating_sub(b));
+		});
+	}
+
+	#[test]
+	fn dropped_imbalances_net_out(steps in prop::collection::vec((any::<bool>(), amount()), 0..32)) {
+		new_test_ext().execute_with(|| {
+			let mut expected = TotalIssuance::<Test>::get();
+			for (positive, value) in steps {
+				if positive {
+					drop(PositiveImbalance::<Test>::for_test(value));
+					expected = expected.saturating_add(value);
+				} else {
+					drop(NegativeImbalance::<Test>::for_test(value));
+					expected = expected.saturating_sub(value);
+				}
+				assert_eq!(TotalIssuance::<Test>::get(), expected);
+			}
+		});
+	}
+
+	#[test]
+	fn issue_and_burn_are_undone_by_their_imbalances(
+		steps in prop::collection::vec((any::<bool>(), capped_amount()), 0..32),
+	) {
+		new_test_ext().execute_with(|| {
+			let initial = TotalIssuance::<Test>::get();
+			let mut expected = initial;
+			let mut issued = Vec::new();
+			let mut burned = Vec::new();
+			for (issue, value) in steps {
+				if issue {
+					let imbalance = KryptoKurrency::issue(value);
+					assert_eq!(imbalance.peek(), value.min(MAX_TOKEN_SUPPLY - expected));
+					expected += imbalance.peek();
+					issued.push(imbalance);
+				} else {
+					let imbalance = KryptoKurrency::burn(value);
+					assert_eq!(imbalance.peek(), value.min(expected));
+					expected -= imbalance.peek();
+					burned.push(imbalance);
+				}
+				assert_eq!(KryptoKurrency::total_issuance(), expected);
+				assert!(expected <= MAX_TOKEN_SUPPLY);
+			}
+			// Burns first, so that undoing the issues never has to go below zero
+			drop(burned);
+			drop(issued);
+			assert_eq!(TotalIssuance::<Test>::get(), initial);
+		});
+	}
+}
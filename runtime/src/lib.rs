@@ -334,16 +334,80 @@ impl pallet_sudo::Config for Runtime {
 // Configure the pallet-krypt in pallets/krypt.
 parameter_types! {
 	pub const MaxTokenSupplyKrypt : u128 = 21_000_000;
+	pub const MaxBatchSizeKrypt : u32 = 64;
+	pub const MinHoldBeforeBurnKrypt : BlockNumber = 0;
+	pub const PartialMintAllowedKrypt : bool = false;
+	pub const SuppressRedundantSnapshotsKrypt : bool = true;
+	pub const MintRatePerBlockKrypt : u128 = 1_000_000;
+	pub const RecordAccountOriginKrypt : bool = false;
+	pub const MaxMemoLengthKrypt : u32 = 64;
+	pub const HistoryDepthKrypt : u32 = 256;
+	pub const DecimalsKrypt : u8 = 0;
+	pub const OfferExpiryKrypt : BlockNumber = 14_400;
+	pub const MaxOffersPerBlockKrypt : u32 = 64;
+	pub const ExistentialDepositKrypt : u128 = 1;
+	pub const MaxMetadataLengthKrypt : u32 = 32;
+	pub const StatsIntervalKrypt : BlockNumber = 100;
+	pub const MaxAccountsPageKrypt : u32 = 1_000;
+	pub const TransferFeeRateKrypt : Permill = Permill::from_parts(0);
+	pub const DecayRatePerBlockKrypt : Permill = Permill::from_parts(0);
+	pub const MinTransferAmountKrypt : u128 = 0;
+	pub const MaxFreezeReasonLengthKrypt : u32 = 128;
+	pub const SaturatingArithmeticKrypt : bool = false;
+	pub const MinMintAmountKrypt : u128 = 0;
+	pub const RoundingKrypt : pallet_krypt::RoundingPolicy = pallet_krypt::RoundingPolicy::Down;
 }
 
 impl pallet_krypt::Config for Runtime {
 	type Event = Event;
 	type Balance = Balance;
 	type MaxTokenSupply = MaxTokenSupplyKrypt;
+	type MaxBatchSize = MaxBatchSizeKrypt;
+	type MinHoldBeforeBurn = MinHoldBeforeBurnKrypt;
+	type PartialMintAllowed = PartialMintAllowedKrypt;
+	type SuppressRedundantSnapshots = SuppressRedundantSnapshotsKrypt;
+	type MintRatePerBlock = MintRatePerBlockKrypt;
+	type RecordAccountOrigin = RecordAccountOriginKrypt;
+	type MaxMemoLength = MaxMemoLengthKrypt;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type MintOrigin = frame_system::EnsureRoot<AccountId>;
+	type ExistentialDeposit = ExistentialDepositKrypt;
+	type TransferFeeRate = TransferFeeRateKrypt;
+	type MaxMetadataLength = MaxMetadataLengthKrypt;
+	type SelfCapOverflowSink = ();
+	type MaxBalancePerAccount = ();
+	type DecayRatePerBlock = DecayRatePerBlockKrypt;
+	type OnBalanceChanged = ();
+	type MinTransferAmount = MinTransferAmountKrypt;
+	type MaxFreezeReasonLength = MaxFreezeReasonLengthKrypt;
+	type SaturatingArithmetic = SaturatingArithmeticKrypt;
+	type MinMintAmount = MinMintAmountKrypt;
+	type Rounding = RoundingKrypt;
+	type Signature = Signature;
+	type Signer = <Signature as Verify>::Signer;
+	type HistoryDepth = HistoryDepthKrypt;
+	type Decimals = DecimalsKrypt;
+	type OfferExpiry = OfferExpiryKrypt;
+	type MaxOffersPerBlock = MaxOffersPerBlockKrypt;
+	type StatsInterval = StatsIntervalKrypt;
+	type MaxAccountsPage = MaxAccountsPageKrypt;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	Call: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = Call;
 }
 
 parameter_types! {
 	pub const MaxTokenSupplyKryptoKurrency : u32 = 100_000;
+	pub const MaxReservesKryptoKurrency : u32 = 50;
+	pub const MaxLocksKryptoKurrency : u32 = 50;
+	pub const DustTrapKryptoKurrency : Option<AccountId> = None;
+	pub const HoldReserveIdKryptoKurrency : [u8; 8] = *b"fun/hold";
+	pub const DisputeReserveIdKryptoKurrency : [u8; 8] = *b"disputes";
 }
 
 impl pallet_kryptokurrency::Config for Runtime {
@@ -351,6 +415,15 @@ impl pallet_kryptokurrency::Config for Runtime {
 	type Balance = Balance;
 	type MaxTokenSupply = MaxTokenSupplyKryptoKurrency;
 	type ExistentialDeposit = ExistentialDeposit;
+	type ReserveIdentifier = [u8; 8];
+	type MaxReserves = MaxReservesKryptoKurrency;
+	type MaxLocks = MaxLocksKryptoKurrency;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type MintHandler = ();
+	type SlashHandler = ();
+	type DustTrap = DustTrapKryptoKurrency;
+	type HoldReserveId = HoldReserveIdKryptoKurrency;
+	type DisputeReserveId = DisputeReserveIdKryptoKurrency;
 }
 
 construct_runtime!(
@@ -401,7 +474,33 @@ pub type Executive = frame_executive::Executive<
 
 sp_api::decl_runtime_apis! {
 	pub trait KryptTotal {
+		/// Current krypt supply, read straight from storage without submitting a transaction
 		fn total_issuance_krypt_api() -> u128;
+		/// Median krypt balance across all holders
+		fn median_balance_krypt_api() -> u128;
+	}
+
+	pub trait KryptAccounts {
+		/// Every restriction currently applying to an account
+		fn account_status_krypt_api(who: AccountId) -> pallet_krypt::AccountStatus;
+	}
+
+	pub trait KryptFreezes {
+		/// Whether an account is frozen
+		fn is_frozen_krypt_api(who: AccountId) -> bool;
+		/// Reason an account was frozen for, `None` if it isn't frozen
+		fn freeze_reason_krypt_api(who: AccountId) -> Option<Vec<u8>>;
+	}
+
+	pub trait KryptoKurrencyBalances {
+		/// Active balance locks of an account as (id, amount) pairs
+		fn locks_kryptokurrency_api(who: AccountId) -> Vec<([u8; 8], u128)>;
+		/// Reserved balance of an account
+		fn reserved_kryptokurrency_api(who: AccountId) -> u128;
+		/// Free and locked balance of an account in one call, the default if it doesn't exist
+		fn account_data_kryptokurrency_api(
+			who: AccountId,
+		) -> pallet_kryptokurrency::AccountData<u128>;
 	}
 }
 
@@ -411,6 +510,42 @@ impl_runtime_apis! {
 		fn total_issuance_krypt_api() -> u128 {
 			Krypt::total_issued()
 		}
+
+		fn median_balance_krypt_api() -> u128 {
+			Krypt::median_balance()
+		}
+	}
+
+	impl crate::KryptAccounts<Block> for Runtime {
+		fn account_status_krypt_api(who: AccountId) -> pallet_krypt::AccountStatus {
+			Krypt::account_status(&who)
+		}
+	}
+
+	impl crate::KryptFreezes<Block> for Runtime {
+		fn is_frozen_krypt_api(who: AccountId) -> bool {
+			Krypt::is_frozen(&who)
+		}
+
+		fn freeze_reason_krypt_api(who: AccountId) -> Option<Vec<u8>> {
+			Krypt::freeze_reason(&who).map(|reason| reason.into_inner())
+		}
+	}
+
+	impl crate::KryptoKurrencyBalances<Block> for Runtime {
+		fn locks_kryptokurrency_api(who: AccountId) -> Vec<([u8; 8], u128)> {
+			KryptoKurrency::lock_summary(&who)
+		}
+
+		fn reserved_kryptokurrency_api(who: AccountId) -> u128 {
+			KryptoKurrency::reserved_balance(&who)
+		}
+
+		fn account_data_kryptokurrency_api(
+			who: AccountId,
+		) -> pallet_kryptokurrency::AccountData<u128> {
+			KryptoKurrency::account_data(&who)
+		}
 	}
 
 	impl sp_api::Core<Block> for Runtime {
@@ -560,6 +695,7 @@ impl_runtime_apis! {
 			list_benchmark!(list, extra, frame_system, SystemBench::<Runtime>);
 			// list_benchmark!(list, extra, pallet_balances, Balances);
 			list_benchmark!(list, extra, pallet_timestamp, Timestamp);
+			list_benchmark!(list, extra, pallet_krypt, Krypt);
 			// list_benchmark!(list, extra, pallet_template, TemplateModule);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
@@ -598,8 +734,78 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			// add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_krypt, Krypt);
 
 			Ok(batches)
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		runtime_decl_for_KryptTotal::KryptTotal,
+		runtime_decl_for_KryptoKurrencyBalances::KryptoKurrencyBalances,
+	};
+	use frame_support::{
+		assert_ok,
+		sp_io::TestExternalities,
+		traits::{Currency, LockableCurrency, ReservableCurrency, WithdrawReasons},
+	};
+
+	fn new_test_ext() -> TestExternalities {
+		let storage = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		let mut ext = TestExternalities::new(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn total_issuance_api_reads_the_krypt_supply_without_events() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(<Runtime as KryptTotal<Block>>::total_issuance_krypt_api(), 0);
+			assert_ok!(Krypt::mint(Origin::root(), 500, AccountId::from([1u8; 32])));
+			let events = System::events().len();
+			assert_eq!(<Runtime as KryptTotal<Block>>::total_issuance_krypt_api(), 500);
+			assert_eq!(System::events().len(), events);
+		});
+	}
+
+	#[test]
+	fn locks_api_reports_every_lock_and_the_reserve() {
+		new_test_ext().execute_with(|| {
+			let who = AccountId::from([1u8; 32]);
+			KryptoKurrency::make_free_balance_be(&who, 1_000);
+			KryptoKurrency::set_lock(*b"vesting ", &who, 300, WithdrawReasons::all());
+			KryptoKurrency::set_lock(*b"staking ", &who, 200, WithdrawReasons::all());
+			assert_ok!(KryptoKurrency::reserve(&who, 100));
+
+			assert_eq!(
+				<Runtime as KryptoKurrencyBalances<Block>>::locks_kryptokurrency_api(who.clone()),
+				vec![(*b"vesting ", 300), (*b"staking ", 200)]
+			);
+			assert_eq!(
+				<Runtime as KryptoKurrencyBalances<Block>>::reserved_kryptokurrency_api(who),
+				100
+			);
+		});
+	}
+
+	#[test]
+	fn account_data_api_returns_free_and_locked_together() {
+		new_test_ext().execute_with(|| {
+			let who = AccountId::from([1u8; 32]);
+			KryptoKurrency::make_free_balance_be(&who, 1_000);
+			assert_ok!(KryptoKurrency::reserve(&who, 400));
+
+			let account_data = |who| {
+				<Runtime as KryptoKurrencyBalances<Block>>::account_data_kryptokurrency_api(who)
+			};
+			let data = account_data(who);
+			assert_eq!((data.free, data.locked), (600, 400));
+			// Absent accounts read as the default
+			assert_eq!(account_data(AccountId::from([2u8; 32])), Default::default());
+		});
+	}
+}